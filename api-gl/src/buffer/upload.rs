@@ -4,8 +4,7 @@ use crate::{
     api::{types::*, Gl},
     buffer::create_buffer,
 };
-use autograph_api::align_offset;
-use std::{ptr::copy_nonoverlapping, sync::Mutex};
+use std::{collections::VecDeque, ops::Range, ptr::copy_nonoverlapping, sync::Mutex};
 
 pub(crate) struct MappedBuffer {
     buffer: GLuint,
@@ -59,32 +58,242 @@ impl MappedBuffer {
     }
 }
 
+/// How [GpuMapping::map_async] accesses the mapped range, mirroring WebGPU's `GPUMapMode`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum MapMode {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl MapMode {
+    /// The `glBufferStorage`/`glMapNamedBufferRange` access bits `self` needs. Never includes
+    /// `MAP_COHERENT_BIT`: that fast path only ever made sense for [MappedBuffer]'s always-write,
+    /// always-persistent mapping, not a one-off map a caller is explicitly waiting to become ready.
+    fn access_flags(self) -> GLenum {
+        match self {
+            MapMode::Read => gl::MAP_READ_BIT,
+            MapMode::Write => gl::MAP_WRITE_BIT,
+            MapMode::ReadWrite => gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
+        }
+    }
+}
+
+/// An explicitly-mapped buffer range, as returned by [GpuMapping::map_async]: unlike
+/// [MappedBuffer] (always write-only, always persistently mapped), this allocates only the access
+/// [MapMode] asks for (adding `MAP_READ_BIT` for a read map instead of assuming write), and gates
+/// [get_mapped_range](GpuMapping::get_mapped_range)/[get_mapped_range_mut](
+/// GpuMapping::get_mapped_range_mut) on a `glFenceSync` the caller polls instead of the CPU
+/// blocking on the GPU as soon as the map is requested — modeled on WebGPU's `mapAsync`.
+pub(crate) struct GpuMapping {
+    buffer: GLuint,
+    ptr: *mut u8,
+    size: usize,
+    mode: MapMode,
+    flags: GLenum,
+    fence: GLsync,
+}
+
+unsafe impl Send for GpuMapping {}
+
+impl GpuMapping {
+    /// Allocates a `size`-byte buffer with the storage flags `mode` needs, maps it, and places a
+    /// `glFenceSync` so [poll](GpuMapping::poll)/[wait](GpuMapping::wait) can tell once whatever
+    /// GPU work the caller is gating this map on (already recorded, for a read-back) has completed.
+    pub(crate) fn map_async(gl: &Gl, size: usize, mode: MapMode) -> GpuMapping {
+        let storage_flags = mode.access_flags();
+        let buffer = create_buffer(gl, size, storage_flags, None);
+        let map_flags = storage_flags | gl::MAP_UNSYNCHRONIZED_BIT;
+        let ptr = unsafe { gl.MapNamedBufferRange(buffer, 0, size as isize, map_flags) as *mut u8 };
+        let fence = unsafe { gl.FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) };
+        GpuMapping {
+            buffer,
+            ptr,
+            size,
+            mode,
+            flags: map_flags,
+            fence,
+        }
+    }
+
+    /// Non-blockingly checks the `glFenceSync` placed by [map_async](GpuMapping::map_async):
+    /// `true` once the mapped range is safe to access.
+    pub(crate) fn poll(&self, gl: &Gl) -> bool {
+        let status = unsafe { gl.ClientWaitSync(self.fence, 0, 0) };
+        status == gl::ALREADY_SIGNALED || status == gl::CONDITION_SATISFIED
+    }
+
+    /// Blocks until [poll](GpuMapping::poll) would return `true`.
+    pub(crate) fn wait(&self, gl: &Gl) {
+        unsafe {
+            gl.ClientWaitSync(self.fence, gl::SYNC_FLUSH_COMMANDS_BIT, u64::max_value());
+        }
+    }
+
+    /// Borrows `range` of the mapped buffer for the CPU to read. Only valid once
+    /// [poll](GpuMapping::poll)/[wait](GpuMapping::wait) has confirmed the map is ready.
+    pub(crate) fn get_mapped_range(&self, range: Range<usize>) -> &[u8] {
+        assert!(range.end <= self.size);
+        unsafe { std::slice::from_raw_parts(self.ptr.add(range.start), range.end - range.start) }
+    }
+
+    /// Like [get_mapped_range](GpuMapping::get_mapped_range), but mutable; only valid for a
+    /// [MapMode::Write] or [MapMode::ReadWrite] mapping.
+    pub(crate) fn get_mapped_range_mut(&mut self, range: Range<usize>) -> &mut [u8] {
+        assert_ne!(self.mode, MapMode::Read);
+        assert!(range.end <= self.size);
+        unsafe {
+            std::slice::from_raw_parts_mut(self.ptr.add(range.start), range.end - range.start)
+        }
+    }
+
+    /// Flushes the whole range with `glFlushMappedBufferRange` if this is a non-coherent write
+    /// map (the flush path [MappedBuffer] leaves commented out, since its own mapping is always
+    /// coherent), then unmaps and releases the fence.
+    pub(crate) fn unmap(self, gl: &Gl) {
+        if self.mode != MapMode::Read && (self.flags & gl::MAP_COHERENT_BIT) == 0 {
+            unsafe {
+                gl.FlushMappedNamedBufferRange(self.buffer, 0, self.size as isize);
+            }
+        }
+        unsafe {
+            gl.UnmapNamedBuffer(self.buffer);
+            gl.DeleteSync(self.fence);
+        }
+    }
+}
+
 struct UploadBufferInner {
     buffer: MappedBuffer,
-    offset: usize,
+    /// Next byte to allocate from, as a virtual (monotonically increasing, never wrapped) offset;
+    /// the physical offset actually written to is `head % buffer.size`. Using a virtual cursor
+    /// instead of wrapping it into `0..buffer.size` directly means "does this allocation run past
+    /// the physical end of the buffer" and "does it run into data still outstanding" are both just
+    /// comparisons against `head`/`tail`, with no separate wrapped/not-wrapped case to track.
+    head: u64,
+    /// The oldest virtual offset not yet confirmed reclaimed: `write` must not allocate anything
+    /// that would advance `head` past `tail + buffer.size`, since `[tail, head)` is exactly the
+    /// range the GPU may still be reading from.
+    tail: u64,
+    /// FIFO of outstanding spans: `(end, fence)`, where `fence` (placed by [fence_frame]
+    /// (UploadBuffer::fence_frame)) signals once the GPU has finished consuming everything written
+    /// up to virtual offset `end`. `reclaim_available`/`wait_reclaim` pop entries off the front and
+    /// advance `tail` to their `end` as their fences signal.
+    pending: VecDeque<(u64, GLsync)>,
+    /// The virtual offset [fence_frame](UploadBuffer::fence_frame) last placed a fence up to, i.e.
+    /// where the next one's span should start from.
+    fenced_up_to: u64,
+}
+
+impl UploadBufferInner {
+    /// Pops every `pending` entry whose fence has already signaled, advancing `tail` past it, in
+    /// FIFO order (fences signal in the order they were placed, so this can stop at the first one
+    /// that hasn't).
+    fn reclaim_available(&mut self, gl: &Gl) {
+        while let Some(&(end, fence)) = self.pending.front() {
+            let status = unsafe { gl.ClientWaitSync(fence, 0, 0) };
+            if status == gl::ALREADY_SIGNALED || status == gl::CONDITION_SATISFIED {
+                unsafe { gl.DeleteSync(fence) };
+                self.tail = end;
+                self.pending.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Blocks on (and pops) `pending` entries, oldest first, until `tail` has reached at least
+    /// `needed_tail` or there's nothing left pending.
+    fn wait_reclaim(&mut self, gl: &Gl, needed_tail: u64) {
+        while self.tail < needed_tail {
+            match self.pending.pop_front() {
+                Some((end, fence)) => {
+                    unsafe {
+                        gl.ClientWaitSync(fence, gl::SYNC_FLUSH_COMMANDS_BIT, u64::max_value());
+                        gl.DeleteSync(fence);
+                    }
+                    self.tail = end;
+                }
+                // Nothing fenced covers `needed_tail` yet (the caller forgot to `fence_frame`, or
+                // is asking for more space than the buffer has): nothing left to wait on.
+                None => break,
+            }
+        }
+    }
 }
 
 pub(crate) struct UploadBuffer(Mutex<UploadBufferInner>);
 
 impl UploadBuffer {
     pub(crate) fn new(buffer: MappedBuffer) -> UploadBuffer {
-        UploadBuffer(Mutex::new(UploadBufferInner { buffer, offset: 0 }))
+        UploadBuffer(Mutex::new(UploadBufferInner {
+            buffer,
+            head: 0,
+            tail: 0,
+            pending: VecDeque::new(),
+            fenced_up_to: 0,
+        }))
     }
 
-    /// Returns the offset.
-    pub(crate) fn write(&self, data: &[u8], align: usize) -> Option<(GLuint, usize)> {
+    /// The total size of the backing [MappedBuffer], for [StagingPool::acquire] to size-check a
+    /// recycled buffer against a new request.
+    pub(crate) fn capacity(&self) -> usize {
+        self.0.lock().unwrap().buffer.size
+    }
+
+    /// Writes `data` at the next `align`-aligned offset in the ring, wrapping to the start of the
+    /// buffer instead of failing once the end is reached, and blocking on whatever's oldest still
+    /// outstanding (per [fence_frame](UploadBuffer::fence_frame)) if that's what stands in the way.
+    /// Returns `None` only if `data` is larger than the buffer could ever hold.
+    pub(crate) fn write(&self, gl: &Gl, data: &[u8], align: usize) -> Option<(GLuint, usize)> {
         let mut self_ = self.0.lock().unwrap();
+        self_.reclaim_available(gl);
 
-        let offset = align_offset(
-            data.len() as u64,
-            align as u64,
-            (self_.offset as u64)..(self_.buffer.size as u64),
-        )? as usize;
+        let size = self_.buffer.size as u64;
+        let len = data.len() as u64;
+        if len > size {
+            return None;
+        }
+
+        // Align within the physical ring, then skip to the start of the next lap if the aligned
+        // span would run past the buffer's physical end.
+        let mut start = self_.head;
+        let align = align as u64;
+        let phys = start % size;
+        let aligned_phys = (phys + align - 1) / align * align;
+        start += aligned_phys - phys;
+        if start % size + len > size {
+            start += size - start % size;
+        }
+        let end = start + len;
+
+        // `[tail, head)` is outstanding GPU work; if this allocation would run into it, wait for
+        // enough of `pending` to free up before overwriting it.
+        if end - self_.tail > size {
+            self_.wait_reclaim(gl, end - size);
+        }
+
+        let offset = (start % size) as usize;
         self_.buffer.write(data, offset);
-        self_.offset = offset + data.len();
+        self_.head = end;
         Some((self_.buffer.raw_buffer(), offset))
     }
 
+    /// Places a `glFenceSync` covering everything [write](UploadBuffer::write)ten since the last
+    /// call to this (or buffer creation), so that span's space is automatically reclaimed by a
+    /// later [write](UploadBuffer::write) once the GPU is confirmed done consuming it. Call this
+    /// once per frame after recording whatever commands read from this buffer.
+    pub(crate) fn fence_frame(&self, gl: &Gl) {
+        let mut self_ = self.0.lock().unwrap();
+        if self_.head == self_.fenced_up_to {
+            // Nothing written since the last fence.
+            return;
+        }
+        let fence = unsafe { gl.FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) };
+        self_.pending.push_back((self_.head, fence));
+        self_.fenced_up_to = self_.head;
+    }
+
     /*pub(crate) fn flush(&self, gl: &Gl) {
         self.0.lock().unwrap().buffer.flush(gl)
     }*/
@@ -93,3 +302,63 @@ impl UploadBuffer {
         self.0.into_inner().unwrap().buffer
     }
 }
+
+/// A free-list of ring [UploadBuffer]s, recycled once the frame that last wrote through them has
+/// retired instead of being reallocated (and re-mapped) every frame, per the piet-gpu-hal
+/// session-style deferred reclaim `autograph_api::Api::retire_frame` drives frontend-side.
+///
+/// FIXME: nothing calls `acquire`/`release`/`retire_frame` yet — the concrete `OpenGlBackend` /
+/// `GlArena` types that would own a `StagingPool` and forward
+/// `autograph_api::Instance::retire_frame` into it aren't present in this crate snapshot (the
+/// `backend` and `command` modules referenced from `pipeline/mod.rs` are missing). The pool itself
+/// is self-contained and frame-index-driven already, ready to wire in once those modules exist.
+pub(crate) struct StagingPool {
+    /// Ring buffers idle and ready to reuse for a new upload.
+    free: Mutex<Vec<UploadBuffer>>,
+    /// Ring buffers still in flight, tagged with the frame index whose GPU work must retire
+    /// before the buffer can go back into `free`.
+    in_flight: Mutex<Vec<(u64, UploadBuffer)>>,
+}
+
+impl StagingPool {
+    pub(crate) fn new() -> StagingPool {
+        StagingPool {
+            free: Mutex::new(Vec::new()),
+            in_flight: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns a ring buffer of at least `size` bytes: a recycled one if `free` has one big
+    /// enough, a freshly allocated (and persistently mapped) one otherwise.
+    pub(crate) fn acquire(&self, gl: &Gl, size: usize) -> UploadBuffer {
+        let mut free = self.free.lock().unwrap();
+        if let Some(index) = free.iter().position(|buffer| buffer.capacity() >= size) {
+            return free.swap_remove(index);
+        }
+        drop(free);
+        UploadBuffer::new(MappedBuffer::new(gl, size))
+    }
+
+    /// Hands `buffer` back to the pool, tagged with `frame`: the frame index whose GPU work last
+    /// read from it. It becomes reusable once [retire_frame](StagingPool::retire_frame) is called
+    /// with that frame index or later.
+    pub(crate) fn release(&self, frame: u64, buffer: UploadBuffer) {
+        self.in_flight.lock().unwrap().push((frame, buffer));
+    }
+
+    /// Moves every in-flight ring buffer tagged with a frame `<= frame` back into the free list,
+    /// resetting its write cursor so it can be reused from the start.
+    pub(crate) fn retire_frame(&self, frame: u64) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let mut free = self.free.lock().unwrap();
+        let mut i = 0;
+        while i < in_flight.len() {
+            if in_flight[i].0 <= frame {
+                let (_, buffer) = in_flight.remove(i);
+                free.push(UploadBuffer::new(buffer.into_inner()));
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
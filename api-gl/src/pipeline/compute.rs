@@ -0,0 +1,44 @@
+use crate::{
+    api::{types::*, Gl},
+    backend::{GlArena, OpenGlBackend},
+    command::StateCache,
+};
+use autograph_api::pipeline::{ComputePipelineCreateInfo, SignatureDescription};
+
+use super::{program::create_compute_program, shader::DescriptorMap, GlSignature};
+
+/// Compute counterpart of [super::GlGraphicsPipeline]: just a program and its descriptor map,
+/// since compute pipelines have no fixed-function or vertex-input state.
+#[derive(Clone, Debug)]
+pub struct GlComputePipeline {
+    pub(crate) descriptor_map: DescriptorMap,
+    pub(crate) program: GLuint,
+}
+
+impl GlComputePipeline {
+    pub(crate) fn descriptor_map(&self) -> &DescriptorMap {
+        &self.descriptor_map
+    }
+
+    pub(crate) fn bind(&self, gl: &Gl, state_cache: &mut StateCache) {
+        state_cache.set_program(gl, self.program);
+    }
+}
+
+pub(crate) unsafe fn create_compute_pipeline_internal<'a>(
+    gl: &Gl,
+    arena: &'a GlArena,
+    _root_signature: &'a GlSignature,
+    _root_signature_description: &SignatureDescription,
+    ci: &ComputePipelineCreateInfo<'a, '_, OpenGlBackend>,
+) -> &'a GlComputePipeline {
+    let (program, descriptor_map) =
+        create_compute_program(gl, ci.shader_stage.inner()).expect("failed to create program");
+
+    let g = GlComputePipeline {
+        program,
+        descriptor_map,
+    };
+
+    arena.compute_pipelines.alloc(g)
+}
@@ -13,6 +13,7 @@ use autograph_api::{
 use ordered_float::NotNan;
 
 mod arguments;
+mod compute;
 mod program;
 mod shader;
 mod vao;
@@ -21,13 +22,15 @@ use self::program::create_graphics_program;
 
 pub(crate) use self::{
     arguments::{GlArgumentBlock, GlSignature, StateBlock},
+    compute::{create_compute_pipeline_internal, GlComputePipeline},
     shader::{DescriptorMap, GlShaderModule},
 };
 use crate::format::GlFormatInfo;
 use autograph_api::pipeline::{
-    GraphicsPipelineCreateInfo, ScissorsOwned, SignatureDescription, VertexInputBinding,
-    ViewportsOwned,
+    DynamicStateFlags, GraphicsPipelineCreateInfo, ScissorsOwned, SignatureDescription,
+    VertexInputBinding, ViewportsOwned,
 };
+use autograph_api::vertex::VertexInputRate;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub(crate) struct StaticSamplerEntry {
@@ -60,14 +63,23 @@ pub struct GlGraphicsPipeline {
     pub(crate) descriptor_map: DescriptorMap,
     pub(crate) viewports: ViewportsOwned,
     pub(crate) scissors: ScissorsOwned,
+    pub(crate) dynamic_state: DynamicStateFlags,
     pub(crate) program: GLuint,
     pub(crate) vao: GLuint,
+    /// Maps this pipeline's push-constant byte ranges to the `glProgramUniform*` calls needed to
+    /// actually update them, core GL having no push-constant object of its own; see
+    /// [PushConstantLayout] and [set_push_constants](GlGraphicsPipeline::set_push_constants).
+    pub(crate) push_constants: PushConstantLayout,
 }
 
 impl GlGraphicsPipeline {
     pub(crate) fn descriptor_map(&self) -> &DescriptorMap {
         &self.descriptor_map
     }
+
+    pub(crate) fn dynamic_state(&self) -> DynamicStateFlags {
+        self.dynamic_state
+    }
 }
 
 /// Converts a sequence of VertexInputBinding (one for each vertex buffer) into a VAO.
@@ -104,11 +116,24 @@ pub(crate) fn create_vertex_array_object(gl: &Gl, bindings: &[VertexInputBinding
 
             location += 1;
         }
+
+        unsafe {
+            gl.VertexArrayBindingDivisor(vao, binding_index as u32, instance_divisor(binding.rate));
+        }
     }
 
     vao
 }
 
+/// The per-instance step rate for `rate`, as passed to `glVertexArrayBindingDivisor` (0 meaning
+/// per-vertex, which is also that call's own default).
+fn instance_divisor(rate: VertexInputRate) -> GLuint {
+    match rate {
+        VertexInputRate::PerVertex => 0,
+        VertexInputRate::PerInstance { divisor } => divisor,
+    }
+}
+
 fn collect_vertex_bindings<'a>(
     sig: &'a SignatureDescription<'a>,
     out: &mut Vec<VertexInputBinding<'a>>,
@@ -119,6 +144,219 @@ fn collect_vertex_bindings<'a>(
     out.extend(sig.vertex_inputs.iter().cloned());
 }
 
+//--------------------------------------------------------------------------------------------------
+
+/// Which `glProgramUniform*` entry point (and how many bytes to read for it) a
+/// [PushConstantUniform] needs, mirroring the wgpu GLES backend's push-constant-as-uniform
+/// emulation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum PushConstantType {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+    Int,
+    IVec2,
+    IVec3,
+    IVec4,
+    Mat4,
+}
+
+impl PushConstantType {
+    fn size(self) -> u32 {
+        match self {
+            PushConstantType::Float | PushConstantType::Int => 4,
+            PushConstantType::Vec2 | PushConstantType::IVec2 => 8,
+            PushConstantType::Vec3 | PushConstantType::IVec3 => 12,
+            PushConstantType::Vec4 | PushConstantType::IVec4 => 16,
+            PushConstantType::Mat4 => 64,
+        }
+    }
+}
+
+/// One push-constant-backed uniform: `offset`/`size` is its byte span within the push-constant
+/// block [GlGraphicsPipeline::set_push_constants] writes to, `location` is the
+/// `glGetUniformLocation` result [PushConstantLayout::upload] uploads it through.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct PushConstantUniform {
+    pub(crate) offset: u32,
+    pub(crate) size: u32,
+    pub(crate) location: GLint,
+    pub(crate) ty: PushConstantType,
+}
+
+/// A pipeline's push-constant layout: translates a byte range of push-constant data into the
+/// `glProgramUniform*` calls needed to actually update it, since core GL has no push-constant
+/// object to write to directly — the same trick the wgpu GLES backend uses.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PushConstantLayout {
+    uniforms: Vec<PushConstantUniform>,
+    /// The whole push-constant block's default contents, applied by [GlGraphicsPipeline::bind] each
+    /// time a pipeline is bound so every uniform starts initialized even for a draw that
+    /// never calls [set_push_constants](GlGraphicsPipeline::set_push_constants) itself.
+    defaults: Vec<u8>,
+}
+
+impl PushConstantLayout {
+    fn total_size(&self) -> u32 {
+        self.defaults.len() as u32
+    }
+
+    fn apply_defaults(&self, gl: &Gl, program: GLuint) {
+        if !self.defaults.is_empty() {
+            self.upload(gl, program, 0, &self.defaults);
+        }
+    }
+
+    /// Issues a `glProgramUniform*` call against `program` for every uniform whose byte range
+    /// lies entirely within `offset..offset + data.len()`, reading its value out of `data`
+    /// (indexed relative to `offset`, since `data` is only the updated slice, not the whole
+    /// block). Uniforms the write covers only partly are skipped.
+    fn upload(&self, gl: &Gl, program: GLuint, offset: u32, data: &[u8]) {
+        let written_end = offset + data.len() as u32;
+        for uniform in &self.uniforms {
+            let uniform_end = uniform.offset + uniform.size;
+            if uniform.offset >= written_end || uniform_end <= offset {
+                // Not covered by this write.
+                continue;
+            }
+            let start_in_data = uniform.offset.saturating_sub(offset) as usize;
+            let end_in_data = (uniform_end.min(written_end) - offset) as usize;
+            let bytes = &data[start_in_data..end_in_data];
+            if bytes.len() as u32 != uniform.size {
+                // A write that only partially covers this uniform's range: skip it rather than
+                // upload a partial (and therefore meaningless) value. Callers are expected to
+                // always write whole uniforms at a time.
+                continue;
+            }
+            unsafe { upload_push_constant_uniform(gl, program, uniform.location, uniform.ty, bytes) };
+        }
+    }
+}
+
+unsafe fn upload_push_constant_uniform(
+    gl: &Gl,
+    program: GLuint,
+    location: GLint,
+    ty: PushConstantType,
+    bytes: &[u8],
+) {
+    let f32_ptr = bytes.as_ptr() as *const f32;
+    let i32_ptr = bytes.as_ptr() as *const i32;
+    match ty {
+        PushConstantType::Float => gl.ProgramUniform1fv(program, location, 1, f32_ptr),
+        PushConstantType::Vec2 => gl.ProgramUniform2fv(program, location, 1, f32_ptr),
+        PushConstantType::Vec3 => gl.ProgramUniform3fv(program, location, 1, f32_ptr),
+        PushConstantType::Vec4 => gl.ProgramUniform4fv(program, location, 1, f32_ptr),
+        PushConstantType::Int => gl.ProgramUniform1iv(program, location, 1, i32_ptr),
+        PushConstantType::IVec2 => gl.ProgramUniform2iv(program, location, 1, i32_ptr),
+        PushConstantType::IVec3 => gl.ProgramUniform3iv(program, location, 1, i32_ptr),
+        PushConstantType::IVec4 => gl.ProgramUniform4iv(program, location, 1, i32_ptr),
+        PushConstantType::Mat4 => gl.ProgramUniformMatrix4fv(program, location, 1, 0, f32_ptr),
+    }
+}
+
+/// The [PushConstantType] `glenum` (a `GL_FLOAT`/`GL_FLOAT_VEC2`/... active-uniform type) reflects
+/// to, or `None` for any type this emulation doesn't carry as a push constant (samplers, opaque
+/// handles, matrix shapes other than 4x4, ...).
+fn push_constant_type_of(glenum: GLenum) -> Option<PushConstantType> {
+    match glenum {
+        gl::FLOAT => Some(PushConstantType::Float),
+        gl::FLOAT_VEC2 => Some(PushConstantType::Vec2),
+        gl::FLOAT_VEC3 => Some(PushConstantType::Vec3),
+        gl::FLOAT_VEC4 => Some(PushConstantType::Vec4),
+        gl::INT => Some(PushConstantType::Int),
+        gl::INT_VEC2 => Some(PushConstantType::IVec2),
+        gl::INT_VEC3 => Some(PushConstantType::IVec3),
+        gl::INT_VEC4 => Some(PushConstantType::IVec4),
+        gl::FLOAT_MAT4 => Some(PushConstantType::Mat4),
+        _ => None,
+    }
+}
+
+/// Builds `program`'s push-constant [PushConstantLayout] by reflecting its active uniforms through
+/// `glGetActiveUniform`/`glGetUniformLocation`.
+///
+/// There is no SPIR-V (or other) source anywhere in this codebase that declares "this signature
+/// also has a push-constant block with members X, Y, Z" — [SignatureDescription] has no such
+/// concept, and neither does upstream SPIR-V reflection (`api/src/pipeline/spirv_reflect.rs`),
+/// which has no handling for the SPIR-V `PushConstant` storage class. So instead of reading a
+/// declared set of members, this reflects `program` itself: every *default-block* active uniform
+/// (`glGetActiveUniformsiv`'s `UNIFORM_BLOCK_INDEX` is `-1`, i.e. not inside a named `uniform`
+/// block) whose type is one [push_constant_type_of] recognizes is treated as a push-constant
+/// member, packed tightly (no padding) into the block in `glGetActiveUniform`'s enumeration order.
+/// Named uniform blocks and samplers/opaque types are left alone — those are descriptor-bound
+/// resources, reflected and bound through [DescriptorMap] instead.
+fn reflect_push_constants(gl: &Gl, program: GLuint) -> PushConstantLayout {
+    let mut active_uniforms = 0;
+    unsafe {
+        gl.GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut active_uniforms);
+    }
+
+    let mut uniforms = Vec::new();
+    let mut offset = 0;
+    let mut name_buf = [0u8; 256];
+
+    for index in 0..(active_uniforms as GLuint) {
+        let mut block_index = 0;
+        unsafe {
+            gl.GetActiveUniformsiv(
+                program,
+                1,
+                &index,
+                gl::UNIFORM_BLOCK_INDEX,
+                &mut block_index,
+            );
+        }
+        if block_index != -1 {
+            // Belongs to a named uniform block, not the default block: a descriptor-bound
+            // resource, not a push constant.
+            continue;
+        }
+
+        let mut length = 0;
+        let mut size = 0;
+        let mut ty = 0;
+        unsafe {
+            gl.GetActiveUniform(
+                program,
+                index,
+                name_buf.len() as GLsizei,
+                &mut length,
+                &mut size,
+                &mut ty,
+                name_buf.as_mut_ptr() as *mut GLchar,
+            );
+        }
+
+        let ty = match push_constant_type_of(ty as GLenum) {
+            Some(ty) => ty,
+            // Not a type this emulation carries as a push constant (e.g. a sampler): skip it.
+            None => continue,
+        };
+
+        let location =
+            unsafe { gl.GetUniformLocation(program, name_buf.as_ptr() as *const GLchar) };
+        if location < 0 {
+            // Active per `glGetActiveUniform` but not individually addressable (e.g. optimized out
+            // by the driver): nothing to upload through.
+            continue;
+        }
+
+        let uniform_size = ty.size();
+        uniforms.push(PushConstantUniform {
+            offset,
+            size: uniform_size,
+            location,
+            ty,
+        });
+        offset += uniform_size;
+    }
+
+    let defaults = vec![0u8; offset as usize];
+    PushConstantLayout { uniforms, defaults }
+}
+
 //--------------------------------------------------------------------------------------------------
 pub(crate) unsafe fn create_graphics_pipeline_internal<'a>(
     gl: &Gl,
@@ -174,15 +412,37 @@ pub(crate) unsafe fn create_graphics_pipeline_internal<'a>(
         color_blend_state,
         viewports: ci.viewport_state.viewports.into(),
         scissors: ci.viewport_state.scissors.into(),
+        dynamic_state: ci.dynamic_state,
+        push_constants: reflect_push_constants(gl, program),
     };
 
     arena.graphics_pipelines.alloc(g)
 }
 
 impl GlGraphicsPipeline {
+    /// Writes `data` into this pipeline's push-constant block at `offset`, uploading it as one or
+    /// more `glProgramUniform*` calls against [Self::program] (see [PushConstantLayout::upload]).
+    ///
+    /// FIXME: the real push-constant API this emulates (e.g. Vulkan's `vkCmdPushConstants`) takes a
+    /// shader-stage mask so a push-constant block can be split vertex/fragment-only; that isn't
+    /// threaded through here since every uniform already carries its own `glGetUniformLocation`
+    /// result and `glProgramUniform*` updates only the program it's passed, but it does mean a
+    /// caller can't restrict a write to "vertex stage only" the way the real API allows. Likewise
+    /// there's no [StateCache]-level dirty-range cache to skip redundant uploads across binds of the
+    /// same pipeline, since `command.rs` (where [StateCache] would need to track that) doesn't
+    /// exist in this crate snapshot.
+    pub(crate) fn set_push_constants(&self, gl: &Gl, offset: u32, data: &[u8]) {
+        self.push_constants.upload(gl, self.program, offset, data);
+    }
+
+    pub(crate) fn push_constants_size(&self) -> u32 {
+        self.push_constants.total_size()
+    }
+
     pub(crate) fn bind(&self, gl: &Gl, state_cache: &mut StateCache) {
         state_cache.set_program(gl, self.program);
         state_cache.set_vertex_array(gl, self.vao);
+        self.push_constants.apply_defaults(gl, self.program);
         state_cache.set_cull_mode(gl, self.rasterization_state.cull_mode);
         state_cache.set_polygon_mode(gl, self.rasterization_state.polygon_mode);
         state_cache.set_stencil_test(gl, &self.depth_stencil_state.stencil_test);
@@ -199,12 +459,19 @@ impl GlGraphicsPipeline {
                 }
             }
         }
-        // static viewports & scissors
-        if let ViewportsOwned::Static(ref vp) = &self.viewports {
-            state_cache.set_viewports(gl, vp);
+        // Static viewports & scissors are baked in here; when the corresponding
+        // `DynamicStateFlags` bit is set, the caller is expected to have already pushed the
+        // equivalent `CommandBuffer::set_viewports`/`set_scissors` command before this draw, so
+        // we leave whatever the command stream set in place instead of overwriting it.
+        if !self.dynamic_state.contains(DynamicStateFlags::VIEWPORT) {
+            if let ViewportsOwned::Static(ref vp) = &self.viewports {
+                state_cache.set_viewports(gl, vp);
+            }
         }
-        if let ScissorsOwned::Static(ref sc) = &self.scissors {
-            state_cache.set_scissors(gl, sc);
+        if !self.dynamic_state.contains(DynamicStateFlags::SCISSOR) {
+            if let ScissorsOwned::Static(ref sc) = &self.scissors {
+                state_cache.set_scissors(gl, sc);
+            }
         }
     }
 }
@@ -0,0 +1,118 @@
+//! Transient image memory aliasing: turning [AliasScope] from a declared intent into an actual
+//! bin-packed memory layout.
+//!
+//! [place_aliased_images] is generic over how an aliasable image's live range was computed, not
+//! over [Command](crate::command::Command) itself: see the FIXME on
+//! [Api::submit_frame](crate::Api::submit_frame) for why `Api` can't compute that live range yet.
+use crate::AliasScope;
+
+/// The inclusive range of sortkeys across which an aliasable image is referenced by the sorted
+/// command stream.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LiveRange {
+    pub first_sortkey: u64,
+    pub last_sortkey: u64,
+}
+
+impl LiveRange {
+    /// Whether this range and `other` share any sortkey.
+    pub fn overlaps(&self, other: &LiveRange) -> bool {
+        self.first_sortkey <= other.last_sortkey && other.first_sortkey <= self.last_sortkey
+    }
+}
+
+/// One aliasable image to be placed by [place_aliased_images]: its declared [AliasScope], its
+/// backing-memory requirement, and its live range in the sorted command stream.
+#[derive(Copy, Clone, Debug)]
+pub struct AliasableImage {
+    pub scope: AliasScope,
+    /// Backing memory size in bytes, as reported by the backend (mip chain included).
+    pub size: u64,
+    /// Backing memory alignment in bytes, as required by the backend.
+    pub align: u64,
+    pub live_range: LiveRange,
+}
+
+/// Where an [AliasableImage] was placed by [place_aliased_images]: which pooled block backs it,
+/// and at what byte offset within that block.
+#[derive(Copy, Clone, Debug)]
+pub struct ImagePlacement {
+    pub block: usize,
+    pub offset: u64,
+}
+
+fn align_up(offset: u64, align: u64) -> u64 {
+    if align == 0 {
+        offset
+    } else {
+        (offset + align - 1) / align * align
+    }
+}
+
+/// Bin-packs `images` into the smallest number of shared backing blocks it can, greedily, and
+/// returns each image's placement (parallel to `images`) plus the byte size of each block.
+///
+/// Two images may only share a block if their [AliasScope]s don't overlap
+/// ([AliasScope::overlaps]) and their [LiveRange]s are disjoint ([LiveRange::overlaps] is false):
+/// either condition failing means the two images could be alive, from the backend's point of
+/// view, at the same time. Images are considered in the order given; each is placed in the first
+/// existing block none of whose current occupants conflict with it, or a new block if none
+/// qualifies. A block's size is the max of its occupants' `size` (aligned to the max of their
+/// `align`), since non-overlapping occupants never need distinct offsets.
+///
+/// This is a greedy first-fit packing, not an optimal one: it can use more blocks than the
+/// theoretical minimum (the classic NP-hard interval-graph-coloring problem), but it's simple,
+/// deterministic, and good enough for the handful of transient images a typical post-process
+/// chain allocates per frame.
+pub fn place_aliased_images(images: &[AliasableImage]) -> (Vec<ImagePlacement>, Vec<u64>) {
+    struct Block {
+        occupants: Vec<usize>,
+        size: u64,
+        align: u64,
+    }
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut placements = vec![
+        ImagePlacement {
+            block: 0,
+            offset: 0
+        };
+        images.len()
+    ];
+
+    for (index, image) in images.iter().enumerate() {
+        let block_index = blocks.iter().position(|block| {
+            block.occupants.iter().all(|&other_index| {
+                let other = &images[other_index];
+                !image.scope.overlaps(&other.scope) && !image.live_range.overlaps(&other.live_range)
+            })
+        });
+
+        let block_index = block_index.unwrap_or_else(|| {
+            blocks.push(Block {
+                occupants: Vec::new(),
+                size: 0,
+                align: 1,
+            });
+            blocks.len() - 1
+        });
+
+        let block = &mut blocks[block_index];
+        block.occupants.push(index);
+        block.size = block.size.max(image.size);
+        block.align = block.align.max(image.align.max(1));
+
+        placements[index] = ImagePlacement {
+            block: block_index,
+            // All occupants of a block have disjoint lifetimes, so they all alias the same
+            // offset: the start of the block.
+            offset: align_up(0, block.align),
+        };
+    }
+
+    let block_sizes = blocks
+        .iter()
+        .map(|block| align_up(block.size, block.align))
+        .collect();
+    (placements, block_sizes)
+}
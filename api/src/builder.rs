@@ -0,0 +1,137 @@
+//! An opt-in background thread for recording command buffers off the submit thread, for scenes
+//! large enough that filling buffers competes with it for CPU time.
+//!
+//! [Api::spawn_builder_thread](crate::Api::spawn_builder_thread) hands back a [BuilderHandle]
+//! that owns the thread and the channel pair it's built on: [BuilderHandle::build] sends a
+//! `record` closure (plus the sortkey range it's expected to stay within) over to the thread,
+//! which records it into a fresh [CommandBuffer] and sends the result back over a reply channel.
+//! [BuilderHandle::collect] picks up whatever's finished so far without blocking;
+//! [BuilderHandle::wait] blocks until a [Checkpoint] (as returned by `build`) has been reached, for
+//! the "give me everything requested up to here" case. Dropping the handle closes the request
+//! channel and joins the thread, so a [BuilderHandle] never outlives the thread it owns.
+//!
+//! This lets an application pipeline scene traversal and command recording (on the builder
+//! thread) against the previous frame's submission (on the main thread) instead of the two
+//! competing for the same core.
+//!
+//! FIXME: this assumes the backend's [CommandBuffer] is `Send` — true of any backend that just
+//! records into a buffer for later submission, with no borrow of the [Instance](crate::Instance)
+//! kept alive across the call — but that bound can't be spelled out explicitly until
+//! `command.rs` exists in this crate snapshot to add it to (see the FIXME on
+//! [Instance::derive_frame_barriers](crate::Instance::derive_frame_barriers) for the same gap).
+use crate::{Backend, CommandBuffer};
+use std::ops::Range;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+struct BuildRequest<B: Backend> {
+    sortkey_range: Range<u64>,
+    record: Box<dyn FnOnce(&mut CommandBuffer<'static, B>) + Send>,
+}
+
+/// A token returned by [BuilderHandle::build], identifying "every request queued up to and
+/// including this one" for [BuilderHandle::wait].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub struct Checkpoint(u64);
+
+/// A handle to a background command-buffer-building thread, as returned by
+/// [Api::spawn_builder_thread](crate::Api::spawn_builder_thread).
+pub struct BuilderHandle<B: Backend> {
+    // `None` once dropped, so `Drop` can close the channel before joining the thread.
+    request_tx: Option<Sender<BuildRequest<B>>>,
+    reply_rx: Receiver<CommandBuffer<'static, B>>,
+    thread: Option<JoinHandle<()>>,
+    sent: u64,
+    received: u64,
+}
+
+impl<B: Backend> BuilderHandle<B> {
+    pub(crate) fn spawn() -> BuilderHandle<B> {
+        let (request_tx, request_rx) = mpsc::channel::<BuildRequest<B>>();
+        let (reply_tx, reply_rx) = mpsc::channel::<CommandBuffer<'static, B>>();
+        let thread = std::thread::Builder::new()
+            .name("autograph-command-builder".to_string())
+            .spawn(move || {
+                for request in request_rx {
+                    let mut command_buffer = CommandBuffer::new();
+                    (request.record)(&mut command_buffer);
+                    // FIXME: nothing checks that `record` only tagged commands within
+                    // `sortkey_range` — see the module doc comment's FIXME on why this can't
+                    // inspect `CommandBuffer`'s contents yet.
+                    let _ = request.sortkey_range;
+                    if reply_tx.send(command_buffer).is_err() {
+                        // The handle (and its reply_rx) was dropped: nothing left to build for.
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn command-builder thread");
+        BuilderHandle {
+            request_tx: Some(request_tx),
+            reply_rx,
+            thread: Some(thread),
+            sent: 0,
+            received: 0,
+        }
+    }
+
+    /// Queues `record` to run on the builder thread against a fresh [CommandBuffer], understood
+    /// to only tag commands with sortkeys inside `sortkey_range` (advisory for now, see the
+    /// module FIXME). Returns a [Checkpoint] [wait](BuilderHandle::wait) can later be passed to
+    /// block until this request, and everything queued before it, has been recorded.
+    pub fn build(
+        &mut self,
+        sortkey_range: Range<u64>,
+        record: impl FnOnce(&mut CommandBuffer<'static, B>) + Send + 'static,
+    ) -> Checkpoint {
+        self.sent += 1;
+        self.request_tx
+            .as_ref()
+            .expect("build called after the builder thread was shut down")
+            .send(BuildRequest {
+                sortkey_range,
+                record: Box::new(record),
+            })
+            .expect("builder thread panicked");
+        Checkpoint(self.sent)
+    }
+
+    /// Non-blockingly collects every command buffer the builder thread has finished recording so
+    /// far, in the order their requests were queued.
+    pub fn collect(&mut self) -> Vec<CommandBuffer<'static, B>> {
+        let mut collected = Vec::new();
+        while let Ok(command_buffer) = self.reply_rx.try_recv() {
+            self.received += 1;
+            collected.push(command_buffer);
+        }
+        collected
+    }
+
+    /// Blocks until every request up to `checkpoint` has been recorded, returning every command
+    /// buffer collected along the way (including later ones, if the builder thread had already
+    /// gotten to them in the meantime).
+    pub fn wait(&mut self, checkpoint: Checkpoint) -> Vec<CommandBuffer<'static, B>> {
+        let mut collected = self.collect();
+        while self.received < checkpoint.0 {
+            match self.reply_rx.recv() {
+                Ok(command_buffer) => {
+                    self.received += 1;
+                    collected.push(command_buffer);
+                }
+                // The builder thread died without replying to everything requested of it.
+                Err(_) => break,
+            }
+        }
+        collected
+    }
+}
+
+impl<B: Backend> Drop for BuilderHandle<B> {
+    fn drop(&mut self) {
+        // Dropping the sender ends the thread's `for request in request_rx` loop.
+        self.request_tx.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
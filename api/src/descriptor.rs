@@ -0,0 +1,70 @@
+//! Descriptor bindings: declared interface slots recovered by shader reflection or hand-built
+//! through [crate::pipeline::DynamicSignatureBuilder], and the concrete resources bound to those
+//! slots when an argument block is assembled.
+use crate::{image::SamplerDescription, pipeline::ShaderStageFlags, Backend};
+
+/// The kind of resource a [ResourceBinding] slot expects.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DescriptorType {
+    UniformBuffer,
+    StorageBuffer,
+    SampledImage,
+    StorageImage,
+    Sampler,
+}
+
+/// One descriptor-set/binding slot declared by a shader stage's interface.
+///
+/// Produced automatically by SPIR-V reflection (see [crate::pipeline::spirv_reflect]) or pushed
+/// by hand via [crate::pipeline::DynamicSignatureBuilder::descriptor].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ResourceBinding<'a> {
+    pub set: u32,
+    pub binding: u32,
+    /// The binding's debug name, if the shader module retained one (e.g. a SPIR-V `OpName`).
+    /// Empty if unknown. See [SamplerDescription::from_binding_name_suffix] for the
+    /// `_sampler_xyz` naming convention this is used to recognize.
+    pub name: &'a str,
+    pub descriptor_type: DescriptorType,
+    /// Number of elements, for an array-of-resources binding; `1` for a single resource.
+    pub count: u32,
+    /// Shader stages that reference this slot; the union of every stage's usage once fused into
+    /// a signature (see [crate::pipeline::spirv_reflect::from_reflected_stages]).
+    pub stage_flags: ShaderStageFlags,
+    /// Set when `name` matched the `_sampler_xyz` naming convention: the binding is still an
+    /// ordinary [DescriptorType::SampledImage] slot, but the backend pins this sampler into the
+    /// pipeline layout instead of expecting one bound per draw (see
+    /// [crate::pipeline::DynamicSignatureBuilder::immutable_sampler]).
+    pub immutable_sampler: Option<SamplerDescription>,
+}
+
+/// A concrete resource bound to a [ResourceBinding] slot, recorded in a
+/// [crate::pipeline::DynamicArgumentBlockBuilder].
+#[derive(derivative::Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+pub enum Descriptor<'a, B: Backend> {
+    UniformBuffer {
+        set: u32,
+        binding: u32,
+        buffer: &'a B::Buffer,
+        offset: u64,
+        size: u64,
+    },
+    StorageBuffer {
+        set: u32,
+        binding: u32,
+        buffer: &'a B::Buffer,
+        offset: u64,
+        size: u64,
+    },
+    SampledImage {
+        set: u32,
+        binding: u32,
+        image: &'a B::Image,
+    },
+    StorageImage {
+        set: u32,
+        binding: u32,
+        image: &'a B::Image,
+    },
+}
@@ -0,0 +1,106 @@
+//! A waitable handle for a submitted frame's GPU completion, modeled on the `GpuFuture` pattern:
+//! [FrameFuture::is_finished]/[FrameFuture::wait] for querying or blocking on completion,
+//! [FrameFuture::cleanup_finished] for non-blockingly reclaiming what waited on it, and
+//! [FrameFuture::join]/[FrameFuture::then_signal_semaphore]/[FrameFuture::then_execute] for
+//! chaining further GPU work after it without the CPU waiting in between.
+use crate::{Api, Backend, CommandBuffer};
+use std::sync::Arc;
+
+/// A handle to a frame submitted via [Api::submit_frame], backed by the backend's own
+/// [Backend::FrameSync] token (a fence or equivalent). This is what lets a caller throttle
+/// CPU-ahead-of-GPU, chain further work after a frame purely on the GPU timeline, or retire pooled
+/// resources (see [Api::retire_frame]) once it's done, instead of `submit_frame` being
+/// fire-and-forget.
+pub struct FrameFuture<'a, B: Backend> {
+    instance: &'a B::Instance,
+    /// The frame index [Api::retire_frame] reclaims up to once this future reports finished.
+    frame: u64,
+    /// Shared with [Api]'s own in-flight bookkeeping (deferred-destruction retirement and the
+    /// [ring_frames](Api::ring_frames) throttle both need to poll/wait on the same token this
+    /// future does), hence the `Arc` instead of owning [Backend::FrameSync] outright.
+    sync: Arc<B::FrameSync>,
+}
+
+impl<'a, B: Backend> FrameFuture<'a, B> {
+    pub(crate) fn new(
+        instance: &'a B::Instance,
+        frame: u64,
+        sync: Arc<B::FrameSync>,
+    ) -> FrameFuture<'a, B> {
+        FrameFuture {
+            instance,
+            frame,
+            sync,
+        }
+    }
+
+    /// The frame index this future tracks, as also passed to [Api::retire_frame].
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Non-blocking: whether the GPU has finished this frame's work.
+    pub fn is_finished(&self) -> bool {
+        unsafe { self.instance.is_frame_finished(&self.sync) }
+    }
+
+    /// Blocks the calling thread until the GPU has finished this frame's work.
+    pub fn wait(&self) {
+        unsafe { self.instance.wait_frame(&self.sync) }
+    }
+
+    /// If this frame has finished, calls [Api::retire_frame] for it and returns `true`; otherwise
+    /// does nothing and returns `false`. Prefer this over [wait](FrameFuture::wait) in a steady
+    /// frame loop so retiring a frame's resources never stalls the CPU on the GPU.
+    pub fn cleanup_finished(self, api: &Api<B>) -> bool {
+        if self.is_finished() {
+            api.retire_frame(self.frame);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Combines this future with `other`: [is_finished](JoinedFrameFuture::is_finished)/
+    /// [wait](JoinedFrameFuture::wait) only report done once both are, for code that depends on two
+    /// independently submitted frames.
+    pub fn join(self, other: FrameFuture<'a, B>) -> JoinedFrameFuture<'a, B> {
+        JoinedFrameFuture { a: self, b: other }
+    }
+
+    /// Returns a [Backend::Semaphore] that becomes signaled once this frame has finished, for a
+    /// later submission (possibly on another queue, see [crate::queue]) to wait on instead of the
+    /// CPU blocking via [wait](FrameFuture::wait).
+    pub fn then_signal_semaphore(&self) -> B::Semaphore {
+        unsafe { self.instance.signal_semaphore_after_frame(&self.sync) }
+    }
+
+    /// Submits `command_buffers` as a new frame ordered after this one on the GPU timeline (the
+    /// backend waits on [then_signal_semaphore](FrameFuture::then_signal_semaphore)'s semaphore
+    /// before executing them), returning the new submission's own [FrameFuture].
+    pub fn then_execute(
+        self,
+        api: &'a Api<B>,
+        command_buffers: impl IntoIterator<Item = CommandBuffer<'a, B>>,
+    ) -> FrameFuture<'a, B> {
+        api.submit_frame_after(&self, command_buffers)
+    }
+}
+
+/// Two frames joined by [FrameFuture::join]: finished, for [is_finished](Self::is_finished) and
+/// [wait](Self::wait)'s purposes, only once both of them are.
+pub struct JoinedFrameFuture<'a, B: Backend> {
+    a: FrameFuture<'a, B>,
+    b: FrameFuture<'a, B>,
+}
+
+impl<'a, B: Backend> JoinedFrameFuture<'a, B> {
+    pub fn is_finished(&self) -> bool {
+        self.a.is_finished() && self.b.is_finished()
+    }
+
+    pub fn wait(&self) {
+        self.a.wait();
+        self.b.wait();
+    }
+}
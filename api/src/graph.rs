@@ -0,0 +1,265 @@
+//! A resource-state task graph: a declarative `add_pass(reads, writes, record)` API that replaces
+//! hand-assigning sortkeys and hand-ordering command buffers, inspired by vulkano's task graph.
+//!
+//! [TaskGraph] builds a dependency DAG from each pass's declared resource accesses
+//! (read-after-write, write-after-read, write-after-write), topologically sorts it, assigns
+//! sortkeys to passes that didn't ask for a specific one, validates the result against
+//! [AliasScope] conflicts, and lowers it to the sorted [CommandBuffer] list
+//! [crate::Api::submit_frame] consumes.
+//!
+//! Like [crate::sync] and [crate::alias], the graph is built from already-declared [ResourceKey]
+//! reads/writes rather than by inspecting [Command](crate::command::Command)'s variants — callers
+//! declare a pass's accesses up front via [TaskGraph::add_pass] instead of the graph inferring them
+//! from the commands it records, so this crate snapshot's missing `command.rs` doesn't block it.
+//! What the graph can't do is assign a sortkey *into* an already-built, opaque [CommandBuffer] (its
+//! exact setter API isn't known here either, for the same reason): [TaskGraph::add_pass]'s `record`
+//! callback is therefore handed the assigned sortkey and trusted to build a [CommandBuffer] that
+//! carries it.
+//!
+//! FIXME: hazards should surface as a variant of this crate's shared error type, but `error.rs`
+//! isn't present in this crate snapshot either; [GraphError] is self-contained (it implements
+//! [std::error::Error] itself) so it can be folded into `crate::error::Error` with a `From` impl
+//! once that type exists, without changing [TaskGraph::schedule]'s signature.
+use crate::alias::LiveRange;
+use crate::sync::ResourceKey;
+use crate::{AliasScope, Backend, CommandBuffer};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+/// One resource a pass writes, with the [AliasScope] it was allocated under, for
+/// [TaskGraph::schedule]'s aliasing-hazard check.
+#[derive(Copy, Clone, Debug)]
+pub struct WriteAccess {
+    pub key: ResourceKey,
+    pub alias_scope: AliasScope,
+}
+
+impl WriteAccess {
+    /// A write to a resource that was not allocated under an [AliasScope] (i.e. its scope is
+    /// [AliasScope::no_alias]), and so is never subject to the aliasing-hazard check.
+    pub fn unaliased(key: ResourceKey) -> WriteAccess {
+        WriteAccess {
+            key,
+            alias_scope: AliasScope::no_alias(),
+        }
+    }
+}
+
+/// Why [TaskGraph::schedule] refused to lower the graph to a command stream.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GraphError {
+    /// A pass reads a resource that no earlier pass writes, and that was never registered with
+    /// [TaskGraph::import] either.
+    UnproducedRead(ResourceKey),
+    /// Two resources were declared under [AliasScope]s that don't overlap (i.e. the caller
+    /// promised they'd never be live at the same time), but the schedule has them both live
+    /// (written or read by some pass) at the same point regardless.
+    AliasedWhileLive(ResourceKey, ResourceKey),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphError::UnproducedRead(key) => {
+                write!(f, "pass reads {:?}, but no earlier pass writes it", key)
+            }
+            GraphError::AliasedWhileLive(a, b) => write!(
+                f,
+                "{:?} and {:?} are declared non-overlapping alias scopes but are both live at the \
+                 same point in the schedule",
+                a, b
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+struct Pass<'a, B: Backend> {
+    reads: Vec<ResourceKey>,
+    writes: Vec<WriteAccess>,
+    sortkey: Option<u64>,
+    record: Box<dyn FnOnce(u64) -> CommandBuffer<'a, B> + 'a>,
+}
+
+/// A declarative builder for a frame's command buffers: instead of hand-assigning sortkeys and
+/// hand-ordering post-process passes, each [add_pass](TaskGraph::add_pass) call declares what a
+/// pass reads and writes, and [schedule](TaskGraph::schedule) derives a valid submission order (and
+/// sortkeys) from those declarations, catching common ordering and aliasing mistakes along the way.
+pub struct TaskGraph<'a, B: Backend> {
+    passes: Vec<Pass<'a, B>>,
+    imported: HashSet<ResourceKey>,
+}
+
+impl<'a, B: Backend> TaskGraph<'a, B> {
+    pub fn new() -> TaskGraph<'a, B> {
+        TaskGraph {
+            passes: Vec::new(),
+            imported: HashSet::new(),
+        }
+    }
+
+    /// Marks `key` as already valid coming into this graph (e.g. a persistent texture, or a
+    /// swapchain image acquired outside it), so passes may read it without a producing write
+    /// inside this graph tripping [GraphError::UnproducedRead].
+    pub fn import(&mut self, key: ResourceKey) {
+        self.imported.insert(key);
+    }
+
+    /// Declares a pass that reads `reads` and writes `writes`. `sortkey` pins the pass to a
+    /// specific sortkey instead of letting [schedule](TaskGraph::schedule) assign one
+    /// automatically; leave it `None` unless another part of the frame needs to interleave with
+    /// this pass at a known sortkey. `record` is called during `schedule`, once the pass's sortkey
+    /// has been decided, and must return a [CommandBuffer] tagged with that sortkey.
+    pub fn add_pass(
+        &mut self,
+        reads: impl IntoIterator<Item = ResourceKey>,
+        writes: impl IntoIterator<Item = WriteAccess>,
+        sortkey: Option<u64>,
+        record: impl FnOnce(u64) -> CommandBuffer<'a, B> + 'a,
+    ) {
+        self.passes.push(Pass {
+            reads: reads.into_iter().collect(),
+            writes: writes.into_iter().collect(),
+            sortkey,
+            record: Box::new(record),
+        });
+    }
+
+    /// Topologically sorts the declared passes by their resource dependencies, assigns sortkeys to
+    /// passes that didn't request one (spaced 1000 apart, in schedule order, leaving room for
+    /// explicit sortkeys to interleave), validates the schedule, and lowers it to the sorted
+    /// [CommandBuffer] list [crate::Api::submit_frame] consumes.
+    pub fn schedule(self) -> Result<Vec<CommandBuffer<'a, B>>, GraphError> {
+        let order = self.topological_order()?;
+        self.check_aliasing(&order)?;
+
+        let mut next_auto_sortkey = 0u64;
+        let mut passes: Vec<Option<Pass<'a, B>>> = self.passes.into_iter().map(Some).collect();
+        let mut out = Vec::with_capacity(order.len());
+        for index in order {
+            let pass = passes[index].take().expect("each index appears once");
+            let sortkey = pass.sortkey.unwrap_or(next_auto_sortkey);
+            next_auto_sortkey = next_auto_sortkey.max(sortkey) + 1000;
+            out.push((pass.record)(sortkey));
+        }
+        Ok(out)
+    }
+
+    /// Builds the read-after-write/write-after-read/write-after-write dependency DAG (a pass
+    /// depends on the most recent earlier-declared writer of each resource it reads or writes, and
+    /// a write also depends on every earlier-declared pass that read the resource since that
+    /// writer) and returns a topological order over it via Kahn's algorithm, breaking ties by
+    /// declaration order for a deterministic result.
+    ///
+    /// Edges only ever run from an earlier-declared pass to a later one (each resource's current
+    /// writer and readers are looked up in a single left-to-right scan over declaration order
+    /// before being replaced), so this DAG can never contain a cycle.
+    fn topological_order(&self) -> Result<Vec<usize>, GraphError> {
+        let n = self.passes.len();
+        let mut last_write: HashMap<ResourceKey, usize> = HashMap::new();
+        let mut reads_since_write: HashMap<ResourceKey, Vec<usize>> = HashMap::new();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut in_degree = vec![0usize; n];
+
+        let mut add_edge = |from: usize,
+                            to: usize,
+                            dependents: &mut Vec<Vec<usize>>,
+                            in_degree: &mut Vec<usize>| {
+            dependents[from].push(to);
+            in_degree[to] += 1;
+        };
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            for key in &pass.reads {
+                match last_write.get(key) {
+                    Some(&writer) => add_edge(writer, index, &mut dependents, &mut in_degree),
+                    None if self.imported.contains(key) => {}
+                    None => return Err(GraphError::UnproducedRead(*key)),
+                }
+                reads_since_write.entry(*key).or_default().push(index);
+            }
+            for write in &pass.writes {
+                if let Some(&writer) = last_write.get(&write.key) {
+                    if writer != index {
+                        add_edge(writer, index, &mut dependents, &mut in_degree);
+                    }
+                }
+                for reader in reads_since_write.remove(&write.key).unwrap_or_default() {
+                    if reader != index {
+                        add_edge(reader, index, &mut dependents, &mut in_degree);
+                    }
+                }
+                last_write.insert(write.key, index);
+            }
+        }
+
+        let mut ready: BTreeSet<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(&index) = ready.iter().next() {
+            ready.remove(&index);
+            order.push(index);
+            for &next in &dependents[index] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    ready.insert(next);
+                }
+            }
+        }
+        debug_assert_eq!(order.len(), n, "the dependency DAG can't contain a cycle");
+        Ok(order)
+    }
+
+    /// Checks that no two resources declared under non-overlapping [AliasScope]s end up both live
+    /// (written or read by some pass) at the same point in `order`.
+    fn check_aliasing(&self, order: &[usize]) -> Result<(), GraphError> {
+        let position: HashMap<usize, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(position, &pass_index)| (pass_index, position))
+            .collect();
+
+        let mut aliased: HashMap<ResourceKey, (AliasScope, LiveRange)> = HashMap::new();
+        for pass in &self.passes {
+            for write in &pass.writes {
+                if write.alias_scope != AliasScope::no_alias() {
+                    aliased.entry(write.key).or_insert((
+                        write.alias_scope,
+                        LiveRange {
+                            first_sortkey: u64::max_value(),
+                            last_sortkey: 0,
+                        },
+                    ));
+                }
+            }
+        }
+
+        for (index, pass) in self.passes.iter().enumerate() {
+            let position = position[&index] as u64;
+            let touched = pass
+                .reads
+                .iter()
+                .copied()
+                .chain(pass.writes.iter().map(|write| write.key));
+            for key in touched {
+                if let Some((_, live)) = aliased.get_mut(&key) {
+                    live.first_sortkey = live.first_sortkey.min(position);
+                    live.last_sortkey = live.last_sortkey.max(position);
+                }
+            }
+        }
+
+        let aliased: Vec<(ResourceKey, AliasScope, LiveRange)> = aliased
+            .into_iter()
+            .map(|(key, (scope, live))| (key, scope, live))
+            .collect();
+        for (i, &(key_a, scope_a, live_a)) in aliased.iter().enumerate() {
+            for &(key_b, scope_b, live_b) in &aliased[i + 1..] {
+                if !scope_a.overlaps(&scope_b) && live_a.overlaps(&live_b) {
+                    return Err(GraphError::AliasedWhileLive(key_a, key_b));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Image views and sampler descriptions.
+use crate::Backend;
+
+/// A view of an image bound as a color attachment.
+#[derive(derivative::Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+pub struct RenderTargetView<'a, B: Backend> {
+    pub image: &'a B::Image,
+}
+
+/// A view of an image bound as a depth/stencil attachment.
+#[derive(derivative::Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+pub struct DepthStencilView<'a, B: Backend> {
+    pub image: &'a B::Image,
+}
+
+/// Texel filtering mode, for minification/magnification and mipmap selection.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+/// How a sampler addresses texture coordinates outside of `[0, 1]`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SamplerAddressMode {
+    ClampToBorder,
+    ClampToEdge,
+    Repeat,
+    MirroredRepeat,
+}
+
+/// Describes how an image is sampled: filtering and addressing.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SamplerDescription {
+    pub min_filter: Filter,
+    pub mag_filter: Filter,
+    pub mipmap_mode: Filter,
+    pub address_mode_u: SamplerAddressMode,
+    pub address_mode_v: SamplerAddressMode,
+    pub address_mode_w: SamplerAddressMode,
+}
+
+impl SamplerDescription {
+    /// Parses the `_sampler_xyz` binding-name suffix convention recognized by
+    /// [crate::pipeline::DynamicSignatureBuilder::immutable_sampler]: `x` selects the texel
+    /// filter (`n`=nearest, `l`=linear), `y` the mipmap mode (`n`/`l`), and `z` the address mode
+    /// (`b`=clamp-to-border, `c`=clamp-to-edge, `r`=repeat, `m`=mirror). Returns `None` if `name`
+    /// doesn't end with a recognized `_sampler_xyz` suffix.
+    pub fn from_binding_name_suffix(name: &str) -> Option<SamplerDescription> {
+        let marker = name.rfind("_sampler_")?;
+        let suffix = &name[marker + "_sampler_".len()..];
+        if suffix.len() != 3 {
+            return None;
+        }
+        let mut chars = suffix.chars();
+        let filter = match chars.next()? {
+            'n' => Filter::Nearest,
+            'l' => Filter::Linear,
+            _ => return None,
+        };
+        let mipmap_mode = match chars.next()? {
+            'n' => Filter::Nearest,
+            'l' => Filter::Linear,
+            _ => return None,
+        };
+        let address_mode = match chars.next()? {
+            'b' => SamplerAddressMode::ClampToBorder,
+            'c' => SamplerAddressMode::ClampToEdge,
+            'r' => SamplerAddressMode::Repeat,
+            'm' => SamplerAddressMode::MirroredRepeat,
+            _ => return None,
+        };
+        Some(SamplerDescription {
+            min_filter: filter,
+            mag_filter: filter,
+            mipmap_mode,
+            address_mode_u: address_mode,
+            address_mode_v: address_mode,
+            address_mode_w: address_mode,
+        })
+    }
+}
@@ -29,15 +29,22 @@ extern crate log;
 #[cfg(feature = "glm")]
 pub use nalgebra_glm as glm;
 
+pub mod alias;
 pub mod buffer;
+pub mod builder;
 pub mod command;
 pub mod descriptor;
 pub mod error;
 pub mod format;
+pub mod future;
+pub mod graph;
 pub mod image;
 pub mod pipeline;
 pub mod prelude;
+pub mod queue;
+pub mod readback;
 pub mod swapchain;
+pub mod sync;
 pub mod traits;
 pub mod typedesc;
 mod util;
@@ -53,9 +60,11 @@ pub use autograph_shader_macros::{
 
 use crate::{
     pipeline::{
-        ArgumentBlock, Arguments, BareArgumentBlock, GraphicsPipeline, GraphicsPipelineCreateInfo,
-        GraphicsShaderStages, ReflectedShader, Scissor, ShaderModule, ShaderStageFlags, Signature,
-        SignatureDescription, TypedSignature, Viewport,
+        validate::{validate_compute_pipeline_signature, validate_tessellation_state},
+        ArgumentBlock, Arguments,
+        BareArgumentBlock, ComputePipeline, ComputePipelineCreateInfo, GraphicsPipeline,
+        GraphicsPipelineCreateInfo, GraphicsShaderStages, ReflectedShader, Scissor, ShaderModule,
+        ShaderStageFlags, Signature, SignatureDescription, TypedSignature, Viewport,
     },
     swapchain::Swapchain,
     vertex::{IndexBufferView, VertexBufferView},
@@ -63,7 +72,7 @@ use crate::{
 use autograph_spirv::DroplessArena;
 use std::{
     any::TypeId, collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData, mem,
-    sync::Mutex,
+    sync::{Arc, Mutex},
 };
 
 //--------------------------------------------------------------------------------------------------
@@ -76,8 +85,10 @@ pub enum MemoryType {
     HostReadback,
 }
 
-/// Currently unused.
-#[derive(Copy, Clone, Debug)]
+/// Which hardware queue a command targets, for the multi-queue submission [queue] sets up: splitting
+/// the sorted command stream into one sub-stream per queue and deriving the cross-queue waits a
+/// resource written on one queue needs before it's read on another (see [queue::split_queues]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Queue {
     Graphics,
     Compute,
@@ -111,6 +122,27 @@ impl AliasScope {
 
 //--------------------------------------------------------------------------------------------------
 
+/// A backend-side handle for incrementally assembling an argument block.
+///
+/// An [Instance] implementation hands one out, already allocated for a given signature, from
+/// [Instance::create_arg_block_builder]. Each `set_*` method writes its slot directly into that
+/// allocation (e.g. a mapped descriptor set or a pinned staging buffer) instead of the caller
+/// buffering it, which removes the per-build heap churn of collecting everything into `Vec`s and
+/// `Option`s first and makes it possible to update only the slots that changed on a cached block.
+/// See [crate::pipeline::DynamicArgumentBlockBuilder] for the builder that drives this interface.
+pub trait ArgBlock<'a, B: Backend> {
+    unsafe fn set_inherited(&mut self, index: usize, block: BareArgumentBlock<'a, B>);
+    unsafe fn set_descriptor(&mut self, index: usize, descriptor: Descriptor<'a, B>);
+    unsafe fn set_vertex_buffer(&mut self, index: usize, vertex_buffer: VertexBufferView<'a, B>);
+    unsafe fn set_index_buffer(&mut self, index_buffer: IndexBufferView<'a, B>);
+    unsafe fn set_render_target(&mut self, index: usize, render_target: RenderTargetView<'a, B>);
+    unsafe fn set_depth_stencil_target(&mut self, depth_stencil_target: DepthStencilView<'a, B>);
+    unsafe fn set_viewport(&mut self, index: usize, viewport: Viewport);
+    unsafe fn set_scissor(&mut self, index: usize, scissor: Scissor);
+    /// Consumes the builder and returns the finished, immutable argument block.
+    unsafe fn finalize(self: Box<Self>) -> &'a B::ArgumentBlock;
+}
+
 pub trait Instance<B: Backend> {
     /// Creates a new empty Arena.
     unsafe fn create_arena(&self) -> Box<B::Arena>;
@@ -160,6 +192,26 @@ pub trait Instance<B: Backend> {
     /// TODO
     unsafe fn create_buffer<'a>(&self, arena: &'a B::Arena, size: u64) -> &'a B::Buffer;
 
+    /// Creates a host-visible, host-readable buffer for [readback::HostReadback]: GPU commands can
+    /// copy into it, and [map_readback_buffer_async](Instance::map_readback_buffer_async) can read
+    /// the result back out once that copy has finished executing.
+    unsafe fn create_readback_buffer<'a>(&self, arena: &'a B::Arena, size: u64) -> &'a B::Buffer;
+
+    /// Registers `callback` to run, with a `&[u8]` view of `buffer`'s mapped memory, once the GPU
+    /// work that writes to `buffer` has finished executing. Does not block; pending callbacks run
+    /// as [poll](Instance::poll) is called, typically once per frame.
+    unsafe fn map_readback_buffer_async(
+        &self,
+        buffer: &B::Buffer,
+        callback: Box<dyn FnOnce(&[u8]) + Send>,
+    );
+
+    /// Checks the status of in-flight GPU work and runs any
+    /// [map_readback_buffer_async](Instance::map_readback_buffer_async) callback whose buffer has
+    /// become ready. If `wait` is true, blocks until at least one more callback becomes ready (or
+    /// there are none pending); otherwise returns immediately. Returns whether any callbacks ran.
+    unsafe fn poll(&self, wait: bool) -> bool;
+
     unsafe fn create_shader_module<'a>(
         &self,
         arena: &'a B::Arena,
@@ -175,6 +227,14 @@ pub trait Instance<B: Backend> {
         create_info: &GraphicsPipelineCreateInfo<'a, '_, B>,
     ) -> &'a B::GraphicsPipeline;
 
+    unsafe fn create_compute_pipeline<'a>(
+        &self,
+        arena: &'a B::Arena,
+        root_signature: &'a B::Signature,
+        root_signature_description: &SignatureDescription,
+        create_info: &ComputePipelineCreateInfo<'a, '_, B>,
+    ) -> &'a B::ComputePipeline;
+
     unsafe fn create_signature<'a>(
         &'a self,
         arena: &'a B::Arena,
@@ -182,6 +242,19 @@ pub trait Instance<B: Backend> {
         description: &SignatureDescription,
     ) -> &'a B::Signature;
 
+    /// Creates a signature that isn't owned by any arena, for [pipeline::OwnedSignature]. The
+    /// returned box must eventually be passed to
+    /// [drop_owned_signature](Instance::drop_owned_signature) exactly once.
+    unsafe fn create_owned_signature(
+        &self,
+        inherited: &[&B::Signature],
+        description: &SignatureDescription,
+    ) -> Box<B::Signature>;
+
+    /// Destroys a signature previously returned by
+    /// [create_owned_signature](Instance::create_owned_signature).
+    unsafe fn drop_owned_signature(&self, signature: Box<B::Signature>);
+
     unsafe fn create_argument_block<'a>(
         &self,
         arena: &'a B::Arena,
@@ -196,6 +269,14 @@ pub trait Instance<B: Backend> {
         scissors: impl IntoIterator<Item = Scissor>,
     ) -> &'a B::ArgumentBlock;
 
+    /// Allocates an [ArgBlock] builder for `signature`, for assembling an argument block one slot
+    /// at a time instead of through [Instance::create_argument_block]'s all-at-once interface.
+    unsafe fn create_arg_block_builder<'a>(
+        &self,
+        arena: &'a B::Arena,
+        signature: &'a B::Signature,
+    ) -> Box<dyn ArgBlock<'a, B> + 'a>;
+
     unsafe fn create_host_reference<'a>(
         &self,
         arena: &'a B::Arena,
@@ -206,7 +287,98 @@ pub trait Instance<B: Backend> {
     /// Uploads all referenced host data to the GPU and releases the borrows.
     ///
     /// Precondition: the command list should be sorted by sortkey.
-    unsafe fn submit_frame<'a>(&self, commands: &[Command<'a, B>]);
+    ///
+    /// Returns an opaque [Backend::FrameSync] token tracking this submission's place on the GPU
+    /// timeline, for [Instance::is_frame_finished]/[Instance::wait_frame] to poll or block on, and
+    /// for [future::FrameFuture] (which wraps this token) to expose to callers.
+    unsafe fn submit_frame<'a>(&self, commands: &[Command<'a, B>]) -> B::FrameSync;
+
+    /// Non-blocking: whether the GPU has finished the frame `sync` was returned for.
+    unsafe fn is_frame_finished(&self, sync: &B::FrameSync) -> bool;
+
+    /// Blocks the calling thread until the GPU has finished the frame `sync` was returned for.
+    unsafe fn wait_frame(&self, sync: &B::FrameSync);
+
+    /// Returns a [Backend::Semaphore] that becomes signaled once the frame `sync` was returned for
+    /// has finished on the GPU, for [Instance::submit_frame_after] (or a backend-specific
+    /// equivalent, e.g. another queue's submission) to wait on instead of the CPU blocking via
+    /// [Instance::wait_frame].
+    unsafe fn signal_semaphore_after_frame(&self, sync: &B::FrameSync) -> B::Semaphore;
+
+    /// Like [submit_frame](Instance::submit_frame), but the GPU waits on `wait` (as returned by
+    /// [signal_semaphore_after_frame](Instance::signal_semaphore_after_frame)) before executing
+    /// `commands`, ordering this submission after whichever frame signaled it purely on the GPU
+    /// timeline, without the CPU waiting in between.
+    unsafe fn submit_frame_after<'a>(
+        &self,
+        wait: &B::Semaphore,
+        commands: &[Command<'a, B>],
+    ) -> B::FrameSync;
+
+    /// Derives the barriers [submit_frame](Instance::submit_frame) must insert between `commands`,
+    /// via [sync::derive_barriers]'s access-type conflict analysis.
+    ///
+    /// FIXME: `command.rs` and `buffer.rs` (which would define `Command<'a, B>`,
+    /// `VertexBufferView`, and `IndexBufferView`) aren't present in this crate snapshot yet, so
+    /// there's no way to walk a command and list the [sync::ResourceAccess]es it declares —
+    /// `sync::derive_barriers` is written generically over an already-extracted
+    /// `&[Vec<sync::ResourceAccess>]` (one entry per command, same order as `commands`) for exactly
+    /// this reason. Once `Command`'s variants exist, give this a real default implementation that
+    /// maps `commands` to their accesses (mirroring [crate::descriptor::Descriptor]'s and
+    /// [crate::image::RenderTargetView]'s variants for the resources each command touches) and
+    /// calls through to `sync::derive_barriers`; until then, backends must derive accesses
+    /// themselves and call `sync::derive_barriers` directly — or, if recording one command at a
+    /// time rather than extracting every command's accesses up front, drive a
+    /// [sync::SyncCommandBuffer] instead, so the hazard-tracking logic itself is still shared
+    /// rather than each backend reimplementing it.
+    unsafe fn derive_frame_barriers<'a>(&self, commands: &[Command<'a, B>]) -> Vec<Vec<sync::Barrier>>;
+
+    /// Submits one queue's sub-stream of `commands`, as split out by [queue::split_queues], waiting
+    /// on other queues' timeline values wherever `waits` says to before executing the command at
+    /// that local index, and returns the timeline value this submission itself signals (i.e. how
+    /// many commands `queue` has now had submitted to it, cumulatively) for other queues to wait on
+    /// in a later call.
+    ///
+    /// FIXME: like [submit_frame](Instance::submit_frame), this takes an already-sorted, already
+    /// backend-agnostic command list, not a whole frame; `Api` can't yet call this instead of
+    /// `submit_frame` because splitting `commands` into `queue::split_queues`'s
+    /// `queues_per_command`/`accesses_per_command` inputs requires walking `Command`'s variants to
+    /// read each one's declared [Queue] and [sync::ResourceAccess]es, and `command.rs` isn't present
+    /// in this crate snapshot (see the FIXME on
+    /// [derive_frame_barriers](Instance::derive_frame_barriers) for the same gap). Until then,
+    /// backends that want multi-queue submission must derive `queue::split_queues`'s inputs
+    /// themselves.
+    unsafe fn submit_queue<'a>(
+        &self,
+        queue: Queue,
+        commands: &[Command<'a, B>],
+        waits: &[queue::QueueWait],
+    ) -> u64;
+
+    /// Binds `image`'s backing memory to byte `offset` of the pooled allocation `block`, as placed
+    /// by [alias::place_aliased_images].
+    ///
+    /// `block` indexes the `Vec<u64>` of block sizes [alias::place_aliased_images] returned
+    /// alongside the placement this call's arguments came from: the backend owns one pooled
+    /// allocation per block, sized to at least that many bytes, and is responsible for creating
+    /// (and growing, if a block's required size changed since the last frame) that allocation
+    /// itself.
+    unsafe fn bind_aliased_image(&self, image: &B::Image, block: usize, offset: u64);
+
+    /// Reclaims whatever the backend keeps pooled for frames up to and including `frame` (as
+    /// returned by [submit_frame](Instance::submit_frame)'s caller, see [Api::retire_frame]):
+    /// staging buffers backing [create_immutable_buffer](Instance::create_immutable_buffer)/
+    /// [create_image](Instance::create_image) initial-data uploads, most notably, can go back into
+    /// a free list here instead of being reallocated next frame.
+    unsafe fn retire_frame(&self, frame: u64);
+
+    /// Serializes the backend's opaque pipeline driver cache (e.g. `VkPipelineCache` data) so
+    /// it can be persisted to disk by [pipeline::PipelineCache::save].
+    unsafe fn get_pipeline_cache_data(&self) -> Vec<u8>;
+
+    /// Primes the backend's driver cache from a blob previously returned by
+    /// [get_pipeline_cache_data](Instance::get_pipeline_cache_data).
+    unsafe fn load_pipeline_cache_data(&self, data: &[u8]);
 }
 
 /// Trait implemented by renderer backends.
@@ -231,9 +403,25 @@ pub trait Backend:
     type Buffer: Sync + Debug;
     type ShaderModule: Sync + Debug;
     type GraphicsPipeline: Sync + Debug;
+    type ComputePipeline: Sync + Debug;
     type Signature: Sync + Debug;
     type ArgumentBlock: Sync + Debug;
     type HostReference: Sync + Debug;
+    /// An opaque token representing a submitted frame's place on the GPU timeline (a fence or
+    /// equivalent), returned by [Instance::submit_frame] and polled/waited on by [future::FrameFuture].
+    type FrameSync: Sync + Debug;
+    /// An opaque GPU-side wait/signal primitive, as returned by
+    /// [Instance::signal_semaphore_after_frame] for one submission to order itself after another's
+    /// GPU work without the CPU waiting in between.
+    type Semaphore: Sync + Debug;
+
+    /// Whether this backend's [Instance::create_signature]/[Instance::create_owned_signature] can
+    /// be called concurrently with whatever else is happening on the thread that owns the
+    /// [Instance] — if `true`, and [synchronous_compilation](Api::synchronous_compilation) is
+    /// turned off, [Api::process_pipeline_queue] creates queued signatures on worker threads
+    /// instead of one at a time inline. Defaults to `false` (most backends, e.g. one bound
+    /// to a single OpenGL context, can't).
+    const THREAD_SAFE_SIGNATURE_CREATION: bool = false;
 }
 
 /// Dummy backend for testing purposes.
@@ -259,9 +447,12 @@ impl Backend for DummyBackend {
     type Buffer = ();
     type ShaderModule = ();
     type GraphicsPipeline = ();
+    type ComputePipeline = ();
     type Signature = ();
     type ArgumentBlock = ();
     type HostReference = ();
+    type FrameSync = ();
+    type Semaphore = ();
 }
 
 /// Dummy instance for testing purposes.
@@ -323,6 +514,22 @@ impl Instance<DummyBackend> for DummyInstance {
         unimplemented!()
     }
 
+    unsafe fn create_readback_buffer<'a>(&self, _arena: &'a (), _size: u64) -> &'a () {
+        unimplemented!()
+    }
+
+    unsafe fn map_readback_buffer_async(
+        &self,
+        _buffer: &(),
+        _callback: Box<dyn FnOnce(&[u8]) + Send>,
+    ) {
+        unimplemented!()
+    }
+
+    unsafe fn poll(&self, _wait: bool) -> bool {
+        unimplemented!()
+    }
+
     unsafe fn create_shader_module<'a>(
         &self,
         _arena: &'a (),
@@ -342,6 +549,16 @@ impl Instance<DummyBackend> for DummyInstance {
         unimplemented!()
     }
 
+    unsafe fn create_compute_pipeline<'a>(
+        &self,
+        _arena: &'a (),
+        _root_signature: &'a (),
+        _root_signature_description: &SignatureDescription,
+        _create_info: &ComputePipelineCreateInfo<DummyBackend>,
+    ) -> &'a () {
+        unimplemented!()
+    }
+
     unsafe fn create_signature<'a>(
         &'a self,
         _arena: &'a (),
@@ -351,6 +568,18 @@ impl Instance<DummyBackend> for DummyInstance {
         unimplemented!()
     }
 
+    unsafe fn create_owned_signature(
+        &self,
+        _inherited: &[&()],
+        _description: &SignatureDescription,
+    ) -> Box<()> {
+        unimplemented!()
+    }
+
+    unsafe fn drop_owned_signature(&self, _signature: Box<()>) {
+        unimplemented!()
+    }
+
     unsafe fn create_argument_block<'a>(
         &self,
         _arena: &'a (),
@@ -367,11 +596,71 @@ impl Instance<DummyBackend> for DummyInstance {
         unimplemented!()
     }
 
+    unsafe fn create_arg_block_builder<'a>(
+        &self,
+        _arena: &'a (),
+        _signature: &'a (),
+    ) -> Box<dyn ArgBlock<'a, DummyBackend> + 'a> {
+        unimplemented!()
+    }
+
     unsafe fn create_host_reference<'a>(&self, _arena: &'a (), _data: &'a [u8]) -> &'a () {
         unimplemented!()
     }
 
-    unsafe fn submit_frame<'a>(&self, _commands: &[Command<'a, DummyBackend>]) {
+    unsafe fn submit_frame<'a>(&self, _commands: &[Command<'a, DummyBackend>]) -> () {
+        unimplemented!()
+    }
+
+    unsafe fn is_frame_finished(&self, _sync: &()) -> bool {
+        unimplemented!()
+    }
+
+    unsafe fn wait_frame(&self, _sync: &()) {
+        unimplemented!()
+    }
+
+    unsafe fn signal_semaphore_after_frame(&self, _sync: &()) -> () {
+        unimplemented!()
+    }
+
+    unsafe fn submit_frame_after<'a>(
+        &self,
+        _wait: &(),
+        _commands: &[Command<'a, DummyBackend>],
+    ) -> () {
+        unimplemented!()
+    }
+
+    unsafe fn derive_frame_barriers<'a>(
+        &self,
+        _commands: &[Command<'a, DummyBackend>],
+    ) -> Vec<Vec<sync::Barrier>> {
+        unimplemented!()
+    }
+
+    unsafe fn submit_queue<'a>(
+        &self,
+        _queue: Queue,
+        _commands: &[Command<'a, DummyBackend>],
+        _waits: &[queue::QueueWait],
+    ) -> u64 {
+        unimplemented!()
+    }
+
+    unsafe fn bind_aliased_image(&self, _image: &(), _block: usize, _offset: u64) {
+        unimplemented!()
+    }
+
+    unsafe fn retire_frame(&self, _frame: u64) {
+        unimplemented!()
+    }
+
+    unsafe fn get_pipeline_cache_data(&self) -> Vec<u8> {
+        unimplemented!()
+    }
+
+    unsafe fn load_pipeline_cache_data(&self, _data: &[u8]) {
         unimplemented!()
     }
 }
@@ -407,8 +696,13 @@ pub struct Arena<'r, B: Backend> {
 }
 
 impl<'r, B: Backend> Drop for Arena<'r, B> {
+    /// Defers the actual backend destruction of this arena's objects until
+    /// [Api::retire_frame] confirms the GPU has finished the last frame that could have
+    /// referenced them, instead of destroying them synchronously: the GPU may still be executing
+    /// commands from a frame submitted just before this arena went out of scope.
     fn drop(&mut self) {
-        unsafe { self.instance.drop_arena(self.inner.take().unwrap()) }
+        let arena = self.inner.take().unwrap();
+        self.renderer.defer_destroy(arena);
     }
 }
 
@@ -417,6 +711,11 @@ impl<'r, B: Backend> Arena<'r, B> {
         self.inner.as_ref().unwrap()
     }
 
+    /// Returns the backend instance that owns this arena.
+    pub(crate) fn instance(&self) -> &'r B::Instance {
+        self.instance
+    }
+
     /// Creates a swapchain.
     #[inline]
     pub fn create_swapchain(&self) -> Swapchain<B> {
@@ -487,6 +786,14 @@ impl<'r, B: Backend> Arena<'r, B> {
             panic!("graphics pipeline validation failed");
         }*/
 
+        let errors = validate_tessellation_state(&create_info);
+        if !errors.is_empty() {
+            for e in &errors {
+                log::error!("validation error: {}", e);
+            }
+            panic!("graphics pipeline validation failed");
+        }
+
         GraphicsPipeline {
             inner: unsafe {
                 self.instance.create_graphics_pipeline(
@@ -500,6 +807,38 @@ impl<'r, B: Backend> Arena<'r, B> {
         }
     }
 
+    /// Creates a compute pipeline given the pipeline description passed in create_info
+    /// and information derived from the pipeline interface type.
+    ///
+    /// Panics if `P::SIGNATURE` declares vertex inputs or fragment outputs: those only make
+    /// sense for graphics pipelines (see [validate_compute_pipeline_signature]).
+    pub fn create_compute_pipeline<'a, P: Arguments<'a, B>>(
+        &'a self,
+        create_info: &ComputePipelineCreateInfo<'a, '_, B>,
+    ) -> ComputePipeline<'a, B, TypedSignature<'a, B, P>> {
+        let root_signature = self.renderer.get_cached_signature::<P>();
+
+        let errors = validate_compute_pipeline_signature(P::SIGNATURE);
+        if !errors.is_empty() {
+            for e in &errors {
+                log::error!("validation error: {}", e);
+            }
+            panic!("compute pipeline validation failed");
+        }
+
+        ComputePipeline {
+            inner: unsafe {
+                self.instance.create_compute_pipeline(
+                    self.inner(),
+                    root_signature.0,
+                    P::SIGNATURE,
+                    &create_info,
+                )
+            },
+            signature: root_signature,
+        }
+    }
+
     /// Creates an image.
     ///
     /// If `scope` is not `AliasScope::no_alias()`, the image is considered _aliasable_, meaning
@@ -624,6 +963,17 @@ impl<'r, B: Backend> Arena<'r, B> {
         })
     }
 
+    /// Creates a host-visible, host-readable buffer for reading GPU results back to the CPU.
+    /// See [readback::HostReadback].
+    #[inline]
+    pub fn create_readback_buffer(&self, size: u64) -> readback::HostReadback<'r, B> {
+        readback::HostReadback {
+            instance: self.instance,
+            raw: unsafe { self.instance.create_readback_buffer(self.inner(), size) },
+            size,
+        }
+    }
+
     /// Creates an immutable, device-local GPU buffer containing an object of type T.
     #[inline]
     pub fn upload<T: Copy + 'static>(&self, data: &T) -> Buffer<B, T> {
@@ -710,6 +1060,18 @@ impl<'r, B: Backend> Arena<'r, B> {
         }
     }
 
+    /// Allocates an [ArgBlock] builder for `signature`, for assembling an argument block one slot
+    /// at a time; see [pipeline::DynamicArgumentBlockBuilder].
+    pub fn create_arg_block_builder<'a, S: Signature<'a, B>>(
+        &'a self,
+        signature: S,
+    ) -> Box<dyn ArgBlock<'a, B> + 'a> {
+        unsafe {
+            self.instance
+                .create_arg_block_builder(self.inner(), signature.inner())
+        }
+    }
+
     pub fn create_typed_argument_block<'a, T: Arguments<'a, B>>(
         &'a self,
         args: T,
@@ -732,6 +1094,51 @@ impl<'r, B: Backend> Arena<'r, B> {
 
 //--------------------------------------------------------------------------------------------------
 
+/// Identifies a signature [Api::queue_signature] (or [Api::get_cached_signature]) registered,
+/// independent of the pipeline interface type it was registered for, so [Api::signature_state] can
+/// poll it without needing that type again.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SignatureId(TypeId);
+
+/// The compile state of a signature tracked by [Api]'s cache, as reported by
+/// [Api::signature_state].
+#[derive(Copy, Clone, Debug)]
+pub enum CachedSignatureState<B: Backend> {
+    /// Registered via [Api::queue_signature], not yet picked up by
+    /// [Api::process_pipeline_queue].
+    Queued,
+    /// Picked up by `process_pipeline_queue`, creating either inline or on a worker thread (see
+    /// [Backend::THREAD_SAFE_SIGNATURE_CREATION]).
+    Compiling,
+    /// Created; ready for [Api::get_cached_signature] (or a future pipeline/command that
+    /// referenced it) to use.
+    Ok(*const B::Signature),
+    /// [Instance::create_signature] panicked while creating it.
+    Err,
+}
+
+/// Everything [Api::process_pipeline_queue] needs to create a queued signature without needing the
+/// pipeline interface type it was [queued](Api::queue_signature) for again: working around the
+/// lack of generic associated types that would otherwise let a boxed factory closure borrow [Api]
+/// with that type's own lifetime (see the ATC note on [Backend]'s doc comment). `description` is
+/// already `'static`, and `inherited`'s pointers are the same stable, arena-owned pointers already
+/// stashed in [Api]'s signature cache, so neither needs the original call's lifetime to be created
+/// later from here.
+struct QueuedSignature<B: Backend> {
+    description: &'static SignatureDescription<'static>,
+    inherited: Vec<AssertSend<B::Signature>>,
+}
+
+/// Wraps a raw pointer so it can be moved into a [Api::process_pipeline_queue] worker thread
+/// despite the pointee's type not being declared `Send`/`Sync`. Sound only because
+/// [Backend::THREAD_SAFE_SIGNATURE_CREATION] is the backend's own attestation that this is fine,
+/// and because `process_pipeline_queue` only ever uses this inside a `std::thread::scope`, which
+/// guarantees every thread holding one has finished (and so stopped dereferencing it) before the
+/// pointee could become invalid.
+#[derive(Copy, Clone)]
+struct AssertSend<T>(*const T);
+unsafe impl<T> Send for AssertSend<T> {}
+
 /// Graphics API trait.
 ///
 /// This is the main interface for interacting with a backend.
@@ -751,8 +1158,37 @@ pub struct Api<B: Backend> {
     instance: B::Instance,
     /// Arena for long-lived or cached objects, such as pipeline signatures
     default_arena: Option<Box<B::Arena>>,
-    /// Cache of pipeline signatures
-    signature_cache: Mutex<HashMap<TypeId, *const B::Signature>>,
+    /// State of each signature [get_cached_signature](Api::get_cached_signature)/
+    /// [queue_signature](Api::queue_signature) has registered, by the pipeline interface's
+    /// `TypeId`; see [CachedSignatureState].
+    signature_cache: Mutex<HashMap<TypeId, CachedSignatureState<B>>>,
+    /// Signatures in [CachedSignatureState::Queued], with what [process_pipeline_queue]
+    /// (Api::process_pipeline_queue) needs to actually create them; see [QueuedSignature].
+    pending_signatures: Mutex<HashMap<TypeId, QueuedSignature<B>>>,
+    /// If `true` (the default), [process_pipeline_queue](Api::process_pipeline_queue) creates
+    /// queued signatures inline, one at a time; if `false`, it creates them on worker threads
+    /// instead when the backend allows it (see [Backend::THREAD_SAFE_SIGNATURE_CREATION]). Tests
+    /// and simple apps that just want today's always-blocking behavior can leave this alone.
+    synchronous_compilation: Mutex<bool>,
+    /// Incremented once per [submit_frame](Api::submit_frame) or
+    /// [submit_frame_after](Api::submit_frame_after) call. Arenas are tagged with the
+    /// current value of this counter when they're dropped, and [retire_frame](Api::retire_frame)
+    /// compares that tag against the frame index it's given to decide what's safe to destroy.
+    frame_counter: Mutex<u64>,
+    /// Arenas whose `Drop` ran (via [defer_destroy](Api::defer_destroy)) before the GPU had
+    /// confirmed finishing the frame that could have last referenced them; destroyed for real once
+    /// [retire_frame](Api::retire_frame) (or [submit_frame](Api::submit_frame)'s own opportunistic
+    /// reclaim) catches up to the frame they're tagged with.
+    pending_drops: Mutex<Vec<(u64, Box<B::Arena>)>>,
+    /// How many frames [submit_frame](Api::submit_frame) lets run ahead of the GPU before blocking
+    /// on the oldest in-flight one; see [ring_frames](Api::ring_frames). Defaults to 2 (double
+    /// buffering).
+    ring_frames: Mutex<u32>,
+    /// Frame syncs for submissions not yet confirmed retired, oldest first. Shared (via `Arc`)
+    /// with the [future::FrameFuture] each [submit_frame](Api::submit_frame) call hands back, so
+    /// both the caller and `Api`'s own ring-throttle/reclaim logic can poll or wait on the same
+    /// token.
+    in_flight: Mutex<Vec<(u64, Arc<B::FrameSync>)>>,
 }
 
 impl<B: Backend> Api<B> {
@@ -763,9 +1199,37 @@ impl<B: Backend> Api<B> {
             instance,
             default_arena: Some(default_arena),
             signature_cache: Mutex::new(HashMap::new()),
+            pending_signatures: Mutex::new(HashMap::new()),
+            synchronous_compilation: Mutex::new(true),
+            frame_counter: Mutex::new(0),
+            pending_drops: Mutex::new(Vec::new()),
+            ring_frames: Mutex::new(2),
+            in_flight: Mutex::new(Vec::new()),
         }
     }
 
+    /// How many frames [submit_frame](Api::submit_frame) currently lets run ahead of the GPU
+    /// before blocking on the oldest in-flight one.
+    pub fn ring_frames(&self) -> u32 {
+        *self.ring_frames.lock().unwrap()
+    }
+
+    /// Sets how deep a submission's in-flight window is allowed to get: e.g. 2 for double
+    /// buffering (the default), 3 for triple buffering. Takes effect starting with the next
+    /// [submit_frame](Api::submit_frame) call.
+    pub fn set_ring_frames(&self, ring_frames: u32) {
+        *self.ring_frames.lock().unwrap() = ring_frames;
+    }
+
+    /// Hands `arena` to this `Api` to destroy once the GPU is confirmed done with whatever frame
+    /// could have last referenced it, instead of destroying it immediately: the GPU may still be
+    /// executing commands from a frame submitted just before `arena` went out of scope. Called
+    /// automatically by [Arena]'s `Drop`.
+    pub fn defer_destroy(&self, arena: Box<B::Arena>) {
+        let frame = *self.frame_counter.lock().unwrap();
+        self.pending_drops.lock().unwrap().push((frame, arena));
+    }
+
     pub fn create_arena(&self) -> Arena<B> {
         Arena {
             renderer: self,
@@ -775,30 +1239,163 @@ impl<B: Backend> Api<B> {
         }
     }
 
-    /// Returns or creates the pipeline signature associated to the pipeline interface type.
+    /// Returns or creates the pipeline signature associated to the pipeline interface type,
+    /// blocking until it's ready if it isn't cached yet — regardless of
+    /// [synchronous_compilation](Api::synchronous_compilation), since a pipeline can't be created
+    /// without a real signature. Call [queue_signature](Api::queue_signature) ahead of when a
+    /// pipeline is actually needed (with [process_pipeline_queue](Api::process_pipeline_queue) run
+    /// in between) to avoid this blocking on first use.
     pub fn get_cached_signature<'r, P: Arguments<'r, B>>(&'r self) -> TypedSignature<'r, B, P> {
         let typeid = TypeId::of::<P::UniqueType>();
-        let cached = self.signature_cache.lock().unwrap().get(&typeid).cloned();
-        if let Some(cached) = cached {
-            unsafe { TypedSignature(&*cached, PhantomData) }
-        } else {
-            // signature not created yet
-            let inherited = P::get_inherited_signatures(self);
-            let sig = unsafe {
-                self.instance.create_signature(
-                    self.default_arena.as_ref().unwrap(),
-                    &inherited,
-                    P::SIGNATURE,
-                )
-            };
+        loop {
+            let state = self.signature_cache.lock().unwrap().get(&typeid).cloned();
+            match state {
+                Some(CachedSignatureState::Ok(sig)) => {
+                    return unsafe { TypedSignature(&*sig, PhantomData) };
+                }
+                Some(CachedSignatureState::Err) => {
+                    panic!("signature creation failed for {:?}", typeid)
+                }
+                Some(CachedSignatureState::Queued) | Some(CachedSignatureState::Compiling) => {
+                    // Queued (by us just below, or by an earlier `queue_signature` call) but not
+                    // yet drained by `process_pipeline_queue`: finish it right now instead of
+                    // making the caller wait for their own next `process_pipeline_queue` call.
+                    let queued = self.pending_signatures.lock().unwrap().remove(&typeid);
+                    match queued {
+                        Some(queued) => {
+                            let state = self.create_queued_signature(&queued);
+                            self.signature_cache.lock().unwrap().insert(typeid, state);
+                        }
+                        // Another thread already took it to create it; give it a moment.
+                        None => std::thread::yield_now(),
+                    }
+                }
+                None => {
+                    self.queue_signature::<P>();
+                }
+            }
+        }
+    }
+
+    /// Registers the pipeline interface type `P`'s signature for creation by
+    /// [process_pipeline_queue](Api::process_pipeline_queue), if it isn't already cached or
+    /// queued, returning a [SignatureId] [signature_state](Api::signature_state) can later poll.
+    /// Doesn't create anything itself: an application that wants to warm up a pipeline ahead of
+    /// when it's first drawn should call this as soon as the pipeline interface is known, and run
+    /// `process_pipeline_queue` (once per [submit_frame](Api::submit_frame) is enough) in between.
+    pub fn queue_signature<'r, P: Arguments<'r, B>>(&'r self) -> SignatureId {
+        let typeid = TypeId::of::<P::UniqueType>();
+        if self.signature_cache.lock().unwrap().contains_key(&typeid) {
+            return SignatureId(typeid);
+        }
+        let inherited = P::get_inherited_signatures(self)
+            .into_iter()
+            .map(|sig| AssertSend(sig as *const _))
+            .collect();
+        self.pending_signatures.lock().unwrap().insert(
+            typeid,
+            QueuedSignature {
+                description: P::SIGNATURE,
+                inherited,
+            },
+        );
+        self.signature_cache
+            .lock()
+            .unwrap()
+            .insert(typeid, CachedSignatureState::Queued);
+        SignatureId(typeid)
+    }
+
+    /// The current [CachedSignatureState] of a signature [queued](Api::queue_signature) (directly,
+    /// or via [get_cached_signature](Api::get_cached_signature)), or `None` if `id` was never
+    /// queued on this `Api`.
+    pub fn signature_state(&self, id: SignatureId) -> Option<CachedSignatureState<B>> {
+        self.signature_cache.lock().unwrap().get(&id.0).cloned()
+    }
+
+    /// Drains every signature [queued](Api::queue_signature) since the last call, creating each
+    /// one inline if [synchronous_compilation](Api::synchronous_compilation) is `true` (the
+    /// default) or the backend doesn't report [Backend::THREAD_SAFE_SIGNATURE_CREATION], or on a
+    /// worker thread per signature otherwise, so a pipeline that only now appeared doesn't stall
+    /// whatever thread found it. Call this once per [submit_frame](Api::submit_frame).
+    ///
+    /// FIXME: a command that references a signature still [Queued](CachedSignatureState::Queued)
+    /// or [Compiling](CachedSignatureState::Compiling) when its frame is submitted should be
+    /// skipped (or deferred to a later frame) instead of [get_cached_signature](
+    /// Api::get_cached_signature) blocking on it; that needs walking `Command`'s variants to find
+    /// which ones reference a given signature, and `command.rs` isn't present in this crate
+    /// snapshot (see the FIXME on
+    /// [Instance::derive_frame_barriers](Instance::derive_frame_barriers) for the same gap).
+    pub fn process_pipeline_queue(&self) {
+        let queued: Vec<(TypeId, QueuedSignature<B>)> =
+            self.pending_signatures.lock().unwrap().drain().collect();
+        if queued.is_empty() {
+            return;
+        }
+        for &(typeid, _) in &queued {
             self.signature_cache
                 .lock()
                 .unwrap()
-                .insert(typeid, sig as *const _);
-            TypedSignature(sig, PhantomData)
+                .insert(typeid, CachedSignatureState::Compiling);
+        }
+
+        if !self.synchronous_compilation() && B::THREAD_SAFE_SIGNATURE_CREATION {
+            // Safety: see `AssertSend`'s doc comment — `THREAD_SAFE_SIGNATURE_CREATION` is the
+            // backend's attestation, and `thread::scope` bounds every spawned thread to this call.
+            let this = AssertSend(self as *const Api<B>);
+            std::thread::scope(|scope| {
+                for (typeid, queued) in queued {
+                    let this = this;
+                    scope.spawn(move || {
+                        let api = unsafe { &*this.0 };
+                        let state = api.create_queued_signature(&queued);
+                        api.signature_cache.lock().unwrap().insert(typeid, state);
+                    });
+                }
+            });
+        } else {
+            for (typeid, queued) in queued {
+                let state = self.create_queued_signature(&queued);
+                self.signature_cache.lock().unwrap().insert(typeid, state);
+            }
+        }
+    }
+
+    /// Creates a single queued signature, catching a panic from the backend as
+    /// [CachedSignatureState::Err] instead of propagating it (so one bad signature doesn't take
+    /// down every other creation running alongside it on another worker thread).
+    fn create_queued_signature(&self, queued: &QueuedSignature<B>) -> CachedSignatureState<B> {
+        let inherited: Vec<&B::Signature> = queued
+            .inherited
+            .iter()
+            .map(|ptr| unsafe { &*ptr.0 })
+            .collect();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            self.instance.create_signature(
+                self.default_arena.as_ref().unwrap(),
+                &inherited,
+                queued.description,
+            )
+        }));
+        match result {
+            Ok(sig) => CachedSignatureState::Ok(sig as *const _),
+            Err(_) => CachedSignatureState::Err,
         }
     }
 
+    /// Whether [process_pipeline_queue](Api::process_pipeline_queue) creates queued signatures
+    /// inline (`true`, the default) or offloads them to worker threads when the backend allows it
+    /// (`false`); see [Backend::THREAD_SAFE_SIGNATURE_CREATION].
+    pub fn synchronous_compilation(&self) -> bool {
+        *self.synchronous_compilation.lock().unwrap()
+    }
+
+    /// Sets [synchronous_compilation](Api::synchronous_compilation). Takes effect starting with the
+    /// next [process_pipeline_queue](Api::process_pipeline_queue) call.
+    pub fn set_synchronous_compilation(&self, synchronous_compilation: bool) {
+        *self.synchronous_compilation.lock().unwrap() = synchronous_compilation;
+    }
+
     /// Returns the default swapchain if there is one.
     pub fn default_swapchain(&self) -> Option<Swapchain<B>> {
         unsafe { self.instance.default_swapchain().map(|s| Swapchain(s)) }
@@ -809,15 +1406,150 @@ impl<B: Backend> Api<B> {
         CommandBuffer::new()
     }
 
+    /// Spawns a background thread dedicated to recording command buffers (see [builder]), so
+    /// scene traversal and command recording can be pipelined against the previous frame's
+    /// [submit_frame](Api::submit_frame) instead of competing with it for CPU time. The returned
+    /// [builder::BuilderHandle] joins the thread on drop.
+    pub fn spawn_builder_thread(&self) -> builder::BuilderHandle<B> {
+        builder::BuilderHandle::spawn()
+    }
+
     /// Submits the given command buffers for rendering and ends the current frame.
     ///
     /// Frame-granularity synchronization points happen in this call.
     /// A new frame is implicitly started after this call.
+    ///
+    /// FIXME: this is also where [alias::place_aliased_images] should run, once per frame, to
+    /// turn every aliasable image created this frame (`scope != AliasScope::no_alias()`) into a
+    /// shared-memory placement: scan `commands` for each aliasable image's first and last
+    /// referencing sortkey to build its [alias::LiveRange], call `place_aliased_images`, and call
+    /// [Instance::bind_aliased_image] for each result before `submit_frame` below. That scan needs
+    /// `Command`'s variants to know which images a command references, and `command.rs` isn't
+    /// present in this crate snapshot yet (see the FIXME on
+    /// [Instance::derive_frame_barriers](crate::Instance::derive_frame_barriers) for the same
+    /// gap), so for now aliasable images fall back to their backend's normal, non-shared
+    /// allocation.
+    ///
+    /// Returns a [future::FrameFuture] tracking this submission's place on the GPU timeline:
+    /// [wait](future::FrameFuture::wait) to throttle the CPU to the GPU,
+    /// [cleanup_finished](future::FrameFuture::cleanup_finished) to non-blockingly
+    /// [retire_frame](Api::retire_frame) once it's done, or
+    /// [then_execute](future::FrameFuture::then_execute)/[then_signal_semaphore](future::FrameFuture::then_signal_semaphore)
+    /// to chain further GPU work after it without waiting in between.
     pub fn submit_frame<'a>(
         &self,
         command_buffers: impl IntoIterator<Item = CommandBuffer<'a, B>>,
-    ) {
+    ) -> future::FrameFuture<'_, B> {
         let commands = sort_command_buffers(command_buffers);
-        unsafe { self.instance.submit_frame(&commands) }
+        let sync = unsafe { self.instance.submit_frame(&commands) };
+        self.frame_future(sync)
+    }
+
+    /// Like [submit_frame](Api::submit_frame), but ordered after `after` purely on the GPU
+    /// timeline instead of the CPU waiting on it first. Used by
+    /// [FrameFuture::then_execute](future::FrameFuture::then_execute).
+    pub fn submit_frame_after<'a>(
+        &self,
+        after: &future::FrameFuture<'_, B>,
+        command_buffers: impl IntoIterator<Item = CommandBuffer<'a, B>>,
+    ) -> future::FrameFuture<'_, B> {
+        let commands = sort_command_buffers(command_buffers);
+        let wait = after.then_signal_semaphore();
+        let sync = unsafe { self.instance.submit_frame_after(&wait, &commands) };
+        self.frame_future(sync)
+    }
+
+    /// Registers `sync` as a new in-flight frame, opportunistically reclaims whatever's already
+    /// finished, and blocks on the oldest in-flight frame if that leaves more than
+    /// [ring_frames](Api::ring_frames) still outstanding, before handing back a [future::FrameFuture]
+    /// for the frame just submitted.
+    fn frame_future(&self, sync: B::FrameSync) -> future::FrameFuture<'_, B> {
+        let frame = {
+            let mut frame_counter = self.frame_counter.lock().unwrap();
+            *frame_counter += 1;
+            *frame_counter
+        };
+        self.reclaim_finished();
+        let sync = Arc::new(sync);
+        self.in_flight.lock().unwrap().push((frame, sync.clone()));
+        self.throttle_ring();
+        future::FrameFuture::new(&self.instance, frame, sync)
+    }
+
+    /// Non-blocking: retires every in-flight frame whose fence has already signaled, in submission
+    /// order, stopping at the first one that hasn't (a single queue's fences are assumed to signal
+    /// in submission order, so this doesn't need to check every entry individually).
+    fn reclaim_finished(&self) {
+        let finished_up_to = {
+            let in_flight = self.in_flight.lock().unwrap();
+            in_flight
+                .iter()
+                .take_while(|(_, sync)| unsafe { self.instance.is_frame_finished(sync) })
+                .map(|&(frame, _)| frame)
+                .last()
+        };
+        if let Some(frame) = finished_up_to {
+            self.retire_up_to(frame);
+        }
+    }
+
+    /// Blocks on (and retires) the oldest in-flight frame, repeatedly, until at most
+    /// [ring_frames](Api::ring_frames) remain outstanding — the CPU-ahead-of-GPU throttle that
+    /// gives [set_ring_frames](Api::set_ring_frames) its effect.
+    fn throttle_ring(&self) {
+        let ring_frames = self.ring_frames() as usize;
+        loop {
+            let oldest = {
+                let in_flight = self.in_flight.lock().unwrap();
+                if in_flight.len() <= ring_frames {
+                    None
+                } else {
+                    in_flight.first().map(|(frame, _)| *frame)
+                }
+            };
+            match oldest {
+                Some(frame) => {
+                    {
+                        let in_flight = self.in_flight.lock().unwrap();
+                        if let Some((_, sync)) = in_flight.iter().find(|(f, _)| *f == frame) {
+                            unsafe { self.instance.wait_frame(sync) }
+                        }
+                    }
+                    self.retire_up_to(frame);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Reclaims whatever was waiting on frame `frame` (as returned by
+    /// [submit_frame](Api::submit_frame)) having finished on the GPU: arenas dropped while that
+    /// frame (or an earlier one) was still in flight, following the piet-gpu-hal session model of
+    /// deferring destruction to a fence instead of dropping synchronously, plus the backend's own
+    /// pooled staging buffers via [Instance::retire_frame].
+    ///
+    /// Call this once per frame, after polling for GPU completion (see
+    /// [Instance::poll](Instance::poll)), passing the most recent frame index confirmed finished.
+    /// [submit_frame](Api::submit_frame) also calls this opportunistically and when its in-flight
+    /// window grows past [ring_frames](Api::ring_frames), so calling it yourself is only needed to
+    /// retire frames more eagerly than that.
+    pub fn retire_frame(&self, frame: u64) {
+        self.retire_up_to(frame);
+    }
+
+    fn retire_up_to(&self, frame: u64) {
+        let mut pending_drops = self.pending_drops.lock().unwrap();
+        let mut i = 0;
+        while i < pending_drops.len() {
+            if pending_drops[i].0 <= frame {
+                let (_, arena) = pending_drops.remove(i);
+                unsafe { self.instance.drop_arena(arena) };
+            } else {
+                i += 1;
+            }
+        }
+        drop(pending_drops);
+        self.in_flight.lock().unwrap().retain(|(f, _)| *f > frame);
+        unsafe { self.instance.retire_frame(frame) }
     }
 }
@@ -0,0 +1,149 @@
+//! Persistent pipeline state cache.
+//!
+//! Canonicalizes a [GraphicsPipelineCreateInfo] (which cannot itself be hashed, since it borrows
+//! live shader module references) into a stable [PipelineCacheKey], and reuses an already-built
+//! [GraphicsPipelineTypeless] on a key hit instead of rebuilding. This amortizes the cost of
+//! recreating equivalent pipeline + framebuffer objects every frame, in the same spirit as the
+//! render-pass-descriptor caching in gfx-rs's Metal backend.
+use crate::{
+    pipeline::{
+        AttachmentLayout, ColorBlendState, GraphicsPipelineCreateInfo, GraphicsPipelineTypeless,
+        ShaderModule, ViewportState,
+    },
+    Arena, Backend, Instance,
+};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+/// A stable hash of the state that determines the identity of a built graphics pipeline:
+/// fixed-function state, dynamic-state flags, attachment layout, and each shader stage's kind and
+/// compiled module identity. The content of the stages' bytecode is not hashed, so two modules
+/// created separately from the same bytecode give different keys.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PipelineCacheKey(u64);
+
+impl PipelineCacheKey {
+    /// Computes the key for `create_info`, rendering to `attachment_layout`.
+    pub fn new<B: Backend>(
+        create_info: &GraphicsPipelineCreateInfo<'_, '_, B>,
+        attachment_layout: &AttachmentLayout,
+    ) -> PipelineCacheKey {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+
+        create_info.viewport_state.hash_fixed_function(&mut hasher);
+        create_info.rasterization_state.hash(&mut hasher);
+        create_info.multisample_state.hash(&mut hasher);
+        create_info.depth_stencil_state.hash(&mut hasher);
+        create_info.input_assembly_state.hash(&mut hasher);
+        create_info.tessellation_state.hash(&mut hasher);
+        create_info.dynamic_state.hash(&mut hasher);
+        create_info
+            .color_blend_state
+            .hash_fixed_function(&mut hasher);
+        attachment_layout.hash(&mut hasher);
+
+        hash_stage(&create_info.shader_stages.vertex, &mut hasher);
+        for stage in [
+            create_info.shader_stages.geometry,
+            create_info.shader_stages.fragment,
+            create_info.shader_stages.tess_control,
+            create_info.shader_stages.tess_eval,
+        ]
+        .iter()
+        .flatten()
+        {
+            hash_stage(stage, &mut hasher);
+        }
+
+        PipelineCacheKey(hasher.finish())
+    }
+}
+
+/// Hashes a shader stage by its kind and the identity of its compiled module.
+///
+/// [ShaderModule] doesn't retain the SPIR-V bytecode it was built from (only the backend's
+/// compiled representation and its reflection, which isn't itself hashable end-to-end), so the
+/// module's pointer identity is used as a stand-in for a content hash: callers that reuse the
+/// same compiled [ShaderModule] reference across calls (e.g. one created once per arena) get a
+/// stable key.
+fn hash_stage<H: Hasher, B: Backend>(stage: &ShaderModule<'_, '_, B>, hasher: &mut H) {
+    stage.reflection().stage.hash(hasher);
+    (stage.inner() as *const B::ShaderModule).hash(hasher);
+}
+
+trait HashFixedFunction {
+    fn hash_fixed_function<H: Hasher>(&self, state: &mut H);
+}
+
+impl<'a> HashFixedFunction for ViewportState<'a> {
+    fn hash_fixed_function<H: Hasher>(&self, state: &mut H) {
+        // Static viewports/scissors are applied by the pipeline itself, so their values are part
+        // of its identity; the dynamic variants carry no values.
+        self.viewports.hash(state);
+        self.scissors.hash(state);
+    }
+}
+
+impl<'a> HashFixedFunction for ColorBlendState<'a> {
+    fn hash_fixed_function<H: Hasher>(&self, state: &mut H) {
+        self.logic_op.hash(state);
+        self.blend_constants.hash(state);
+        match self.attachments {
+            crate::pipeline::ColorBlendAttachments::All(a) => {
+                0u8.hash(state);
+                a.hash(state);
+            }
+            crate::pipeline::ColorBlendAttachments::Separate(a) => {
+                1u8.hash(state);
+                a.hash(state);
+            }
+        }
+    }
+}
+
+/// Caches built graphics pipelines keyed on [PipelineCacheKey], and supports persisting the
+/// backend's opaque driver cache blob (`B::GraphicsPipeline` creation data) across runs.
+pub struct PipelineCache<'a, B: Backend> {
+    entries: Mutex<HashMap<PipelineCacheKey, GraphicsPipelineTypeless<'a, B>>>,
+}
+
+impl<'a, B: Backend> PipelineCache<'a, B> {
+    pub fn new() -> PipelineCache<'a, B> {
+        PipelineCache {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the cached pipeline for `key`, or builds it with `build` and inserts it.
+    pub fn get_or_create(
+        &self,
+        key: PipelineCacheKey,
+        build: impl FnOnce() -> GraphicsPipelineTypeless<'a, B>,
+    ) -> GraphicsPipelineTypeless<'a, B> {
+        let mut entries = self.entries.lock().unwrap();
+        *entries.entry(key).or_insert_with(build)
+    }
+
+    /// Serializes the backend's opaque pipeline driver cache (e.g. Vulkan's `VkPipelineCache`
+    /// blob) so it can be written to disk and reloaded on the next run via [Self::load].
+    pub fn save(&self, arena: &Arena<'_, B>) -> Vec<u8> {
+        unsafe { arena.instance().get_pipeline_cache_data() }
+    }
+
+    /// Primes the backend's driver cache from a blob previously returned by [Self::save].
+    ///
+    /// This only warms the driver-level cache (so that equivalent pipelines compile faster);
+    /// it does not repopulate `self`'s own [PipelineCacheKey] entries, which are rebuilt lazily.
+    pub fn load(&self, arena: &Arena<'_, B>, data: &[u8]) {
+        unsafe { arena.instance().load_pipeline_cache_data(data) }
+    }
+}
+
+impl<'a, B: Backend> Default for PipelineCache<'a, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,136 @@
+//! naga-based multi-language shader frontend.
+//!
+//! Parses WGSL or GLSL source into a `naga::Module` (instead of requiring pre-compiled SPIR-V),
+//! validates it, and derives the [ShaderStageReflection] directly from the naga IR. The module is
+//! returned as is in [ParsedShader]: nothing here creates a [ShaderModule] from it or generates
+//! per-backend output (SPIR-V, MSL, HLSL) from it.
+use crate::pipeline::{FragmentOutputDescription, ShaderStageFlags, ShaderStageReflection};
+use autograph_spirv::DroplessArena;
+use std::fmt;
+
+/// Failure parsing or validating a WGSL/GLSL shader source.
+#[derive(Debug)]
+pub enum FrontendError {
+    Parse(String),
+    Validation(String),
+}
+
+impl fmt::Display for FrontendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrontendError::Parse(msg) => write!(f, "shader parse error: {}", msg),
+            FrontendError::Validation(msg) => write!(f, "shader validation error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FrontendError {}
+
+/// The result of parsing and validating a shader source: the naga module (kept around so it can
+/// be re-translated to whatever IR each backend wants) plus the reflection derived from it.
+pub struct ParsedShader<'a> {
+    pub module: naga::Module,
+    pub reflection: &'a ShaderStageReflection<'a>,
+}
+
+/// Parses, validates and reflects a WGSL module.
+///
+/// Unlike GLSL, a WGSL module can define entry points for more than one stage; `entry_point`
+/// selects which one `reflection` describes.
+pub fn parse_wgsl<'a>(
+    arena: &'a DroplessArena,
+    source: &str,
+    entry_point: &str,
+) -> Result<ParsedShader<'a>, FrontendError> {
+    let module =
+        naga::front::wgsl::parse_str(source).map_err(|e| FrontendError::Parse(e.to_string()))?;
+    validate_and_reflect(arena, module, entry_point)
+}
+
+/// Parses, validates and reflects a single-stage GLSL module.
+pub fn parse_glsl<'a>(
+    arena: &'a DroplessArena,
+    source: &str,
+    stage: ShaderStageFlags,
+    entry_point: &str,
+) -> Result<ParsedShader<'a>, FrontendError> {
+    let options = naga::front::glsl::Options {
+        stage: to_naga_stage(stage),
+        defines: Default::default(),
+    };
+    let module = naga::front::glsl::Frontend::default()
+        .parse(&options, source)
+        .map_err(|e| FrontendError::Parse(format!("{:?}", e)))?;
+    validate_and_reflect(arena, module, entry_point)
+}
+
+fn validate_and_reflect<'a>(
+    arena: &'a DroplessArena,
+    module: naga::Module,
+    entry_point: &str,
+) -> Result<ParsedShader<'a>, FrontendError> {
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::all(),
+    )
+    .validate(&module)
+    .map_err(|e| FrontendError::Validation(e.to_string()))?;
+
+    let entry = module
+        .entry_points
+        .iter()
+        .find(|e| e.name == entry_point)
+        .ok_or_else(|| {
+            FrontendError::Validation(format!("no entry point named \"{}\"", entry_point))
+        })?;
+
+    let stage = match entry.stage {
+        naga::ShaderStage::Vertex => ShaderStageFlags::VERTEX,
+        naga::ShaderStage::Fragment => ShaderStageFlags::FRAGMENT,
+        naga::ShaderStage::Compute => ShaderStageFlags::COMPUTE,
+    };
+
+    // FIXME: only fragment outputs are reflected from the naga IR so far; walking
+    // `module.global_variables` into `descriptor::ResourceBinding`s (one per `@group`/`@binding`
+    // pair) is left for a follow-up, as are the vertex input attributes, left empty below.
+    let _ = &info;
+    let fragment_outputs = arena.alloc_extend(reflect_fragment_outputs(&module, entry));
+
+    let reflection = arena.alloc(ShaderStageReflection {
+        stage,
+        descriptors: &[],
+        vertex_input_attributes: &[],
+        fragment_outputs,
+    });
+
+    Ok(ParsedShader { module, reflection })
+}
+
+fn reflect_fragment_outputs(
+    module: &naga::Module,
+    entry: &naga::EntryPoint,
+) -> Vec<FragmentOutputDescription> {
+    if entry.stage != naga::ShaderStage::Fragment {
+        return Vec::new();
+    }
+    let result_count = module.functions[entry.function]
+        .result
+        .as_ref()
+        .map(|_| 1)
+        .unwrap_or(0);
+    (0..result_count)
+        .map(|_| FragmentOutputDescription { resolve: false })
+        .collect()
+}
+
+fn to_naga_stage(stage: ShaderStageFlags) -> naga::ShaderStage {
+    if stage.contains(ShaderStageFlags::VERTEX) {
+        naga::ShaderStage::Vertex
+    } else if stage.contains(ShaderStageFlags::FRAGMENT) {
+        naga::ShaderStage::Fragment
+    } else if stage.contains(ShaderStageFlags::COMPUTE) {
+        naga::ShaderStage::Compute
+    } else {
+        panic!("GLSL frontend requires exactly one of VERTEX, FRAGMENT or COMPUTE")
+    }
+}
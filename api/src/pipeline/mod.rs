@@ -1,22 +1,32 @@
 use crate::{
     buffer::Buffer,
-    descriptor::{Descriptor, ResourceBinding},
+    descriptor::{Descriptor, DescriptorType, ResourceBinding},
     format::Format,
-    image::{DepthStencilView, RenderTargetView},
+    image::{DepthStencilView, RenderTargetView, SamplerDescription},
     vertex::{
         IndexBufferView, IndexData, IndexFormat, Semantic, VertexBufferView, VertexData,
         VertexInputRate, VertexLayout,
     },
-    Arena, Backend, Api,
+    Api, Arena, ArgBlock, Backend, Instance,
 };
 pub use autograph_api_macros::Arguments;
-use autograph_spirv::{TypeDesc};
+use autograph_spirv::TypeDesc;
 use bitflags::bitflags;
 use ordered_float::NotNan;
-use std::{fmt::Debug, marker::PhantomData, mem};
+use std::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+    mem,
+    rc::Rc,
+};
 
+mod cache;
+pub mod frontend;
+pub mod spirv_reflect;
 pub mod validate;
 
+pub use cache::{PipelineCache, PipelineCacheKey};
+
 bitflags! {
     #[derive(Default)]
     pub struct ShaderStageFlags: u32 {
@@ -35,12 +45,20 @@ pub enum PrimitiveTopology {
     PointList,
     LineList,
     TriangleList,
+    /// Patches consumed by the tessellation control/evaluation stages; see [TessellationState].
+    PatchList,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum ShaderFormat {
     SpirV,
     BackendSpecific,
+    /// WGSL source, parsed and reflected via [naga](https://github.com/gfx-rs/naga).
+    Wgsl,
+    /// GLSL source for a single stage, parsed and reflected via naga.
+    Glsl {
+        stage: ShaderStageFlags,
+    },
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -79,6 +97,10 @@ bitflags! {
 }
 
 bitflags! {
+    /// Flags a pipeline's corresponding create-info fields as "set at draw time instead of
+    /// baked into the pipeline". The matching `CommandBuffer::set_viewports`/`set_scissors`/
+    /// `set_blend_constants`/`set_stencil_reference` calls are recorded in `command` like any
+    /// other command and applied right before the draw that needs them.
     #[derive(Default)]
     pub struct DynamicStateFlags: u32 {
         const VIEWPORT = (1 << 0);
@@ -262,6 +284,23 @@ impl Default for InputAssemblyState {
     }
 }
 
+/// Number of control points per patch, for pipelines using [PrimitiveTopology::PatchList] with
+/// the `tess_control`/`tess_eval` stages.
+///
+/// Mirrors vulkano's `TessellationState`/`patch_control_points`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TessellationState {
+    pub patch_control_points: u32,
+}
+
+impl Default for TessellationState {
+    fn default() -> Self {
+        TessellationState {
+            patch_control_points: 1,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum SampleShading {
     Disabled,
@@ -298,7 +337,11 @@ pub struct AttachmentLayout<'a> {
     pub input_attachments: &'a [AttachmentDescription],
     pub depth_attachment: Option<AttachmentDescription>,
     pub color_attachments: &'a [AttachmentDescription],
-    //pub resolve_attachments: &'a [AttachmentDescription]
+    /// Single-sample targets that each multisampled entry in `color_attachments` resolves into.
+    ///
+    /// Empty if no attachment resolves; otherwise the same length as `color_attachments`, with
+    /// a zeroed (unused) entry for outputs whose [FragmentOutputDescription::resolve] is `false`.
+    pub resolve_attachments: &'a [AttachmentDescription],
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
@@ -507,8 +550,27 @@ pub struct GraphicsPipelineCreateInfo<'a, 'b, B: Backend> {
     pub multisample_state: MultisampleState,
     pub depth_stencil_state: DepthStencilState,
     pub input_assembly_state: InputAssemblyState,
+    /// Patch control point count; required (non-zero) when `shader_stages` sets
+    /// `tess_control`/`tess_eval` and `input_assembly_state.topology` is
+    /// [PrimitiveTopology::PatchList], ignored otherwise.
+    pub tessellation_state: TessellationState,
     pub color_blend_state: ColorBlendState<'b>,
-    //pub dynamic_state: DynamicStateFlags,
+    /// States that are set at draw time via `CommandBuffer::set_viewports`/`set_scissors`/
+    /// `set_blend_constants`/`set_stencil_reference` instead of being baked into the pipeline.
+    ///
+    /// For each flag set here, the corresponding field above (`viewport_state`'s viewports,
+    /// `viewport_state`'s scissors, `color_blend_state.blend_constants`, the stencil op states'
+    /// `reference`) is ignored at pipeline build time.
+    pub dynamic_state: DynamicStateFlags,
+}
+
+/// Describes a compute pipeline: a single compute shader stage and nothing else.
+///
+/// Unlike [GraphicsPipelineCreateInfo], there is no fixed-function state to configure: the
+/// local workgroup size is declared by the compute shader itself.
+#[derive(Copy, Clone)]
+pub struct ComputePipelineCreateInfo<'a, 'b, B: Backend> {
+    pub shader_stage: ShaderModule<'a, 'b, B>,
 }
 
 //--------------------------------------------------------------------------------------------------
@@ -659,7 +721,10 @@ impl<'a> SignatureDescription<'a> {
     }
 }
 
-pub trait Signature<'a, B: Backend>: Copy + Clone + Debug {
+/// `Copy` is deliberately not a supertrait here: [DynamicSignature] and [TypedSignature] are both
+/// `Copy` (they only hold arena-borrowed references), but [OwnedSignature] is `Rc`-backed and
+/// can't be. Types that need a `Copy` signature (e.g. [ArgumentBlock]) add the bound themselves.
+pub trait Signature<'a, B: Backend>: Clone + Debug {
     fn inner(&self) -> &'a B::Signature;
     fn description(&self) -> &SignatureDescription;
 }
@@ -690,7 +755,7 @@ impl<'a, B: Backend, T: Arguments<'a, B>> Signature<'a, B> for TypedSignature<'a
 /// The contents of an argument block is described by a [Signature].
 /// See also [SignatureDescription].
 #[derive(derivative::Derivative)]
-#[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+#[derivative(Copy(bound = "S: Copy"), Clone(bound = ""), Debug(bound = ""))]
 pub struct ArgumentBlock<'a, B: Backend, S: Signature<'a, B>> {
     pub(crate) arguments: &'a B::ArgumentBlock,
     pub(crate) signature: S,
@@ -714,7 +779,7 @@ impl<'a, B: Backend, S: Signature<'a, B>> From<ArgumentBlock<'a, B, S>>
 
 /// Graphics pipeline.
 #[derive(derivative::Derivative)]
-#[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+#[derivative(Copy(bound = "S: Copy"), Clone(bound = ""), Debug(bound = ""))]
 pub struct GraphicsPipeline<'a, B: Backend, S: Signature<'a, B>> {
     pub(crate) inner: &'a B::GraphicsPipeline,
     pub(crate) signature: S,
@@ -728,6 +793,22 @@ pub struct GraphicsPipelineTypeless<'a, B: Backend>(pub(crate) &'a B::GraphicsPi
 /// Type alias for argument blocks with a statically known signature.
 pub type TypedGraphicsPipeline<'a, B, T> = GraphicsPipeline<'a, B, TypedSignature<'a, B, T>>;
 
+/// Compute pipeline.
+#[derive(derivative::Derivative)]
+#[derivative(Copy(bound = "S: Copy"), Clone(bound = ""), Debug(bound = ""))]
+pub struct ComputePipeline<'a, B: Backend, S: Signature<'a, B>> {
+    pub(crate) inner: &'a B::ComputePipeline,
+    pub(crate) signature: S,
+}
+
+/// Compute pipeline without an associated signature.
+#[derive(derivative::Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
+pub struct ComputePipelineTypeless<'a, B: Backend>(pub(crate) &'a B::ComputePipeline);
+
+/// Type alias for compute pipelines with a statically known signature.
+pub type TypedComputePipeline<'a, B, T> = ComputePipeline<'a, B, TypedSignature<'a, B, T>>;
+
 /// Trait for types that can be converted into an argument block.
 pub trait IntoArgumentBlock<'a, B: Backend, S: Signature<'a, B>> {
     fn into_block(self, signature: S, arena: &'a Arena<B>) -> ArgumentBlock<'a, B, S>;
@@ -869,7 +950,8 @@ pub struct VertexInputAttributeDescription<'tcx> {
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct FragmentOutputDescription {
-    // nothing yet, we just care about the count
+    /// Whether this output also binds a resolve target (see [AttachmentLayout::resolve_attachments]).
+    pub resolve: bool,
 }
 
 /// Shader reflection information for one stage.
@@ -890,7 +972,9 @@ pub struct ReflectedShader<'bc, 're> {
 
 //--------------------------------------------------------------------------------------------------
 
-// not good: this borrows the builder, cannot be stored in a struct
+/// Borrows its backend signature and description from an [Arena] for the arena's `'a` lifetime,
+/// so it can't be stored in a struct that outlives that arena. See [OwnedSignature] for a
+/// reference-counted alternative that can be.
 #[derive(derivative::Derivative)]
 #[derivative(Copy(bound = ""), Clone(bound = ""), Debug(bound = ""))]
 pub struct DynamicSignature<'a, B: Backend> {
@@ -937,6 +1021,36 @@ impl<'a, B: Backend> DynamicSignatureBuilder<'a, B> {
         self.descriptors.push(d);
         self
     }
+
+    /// Adds a sampled-image descriptor whose sampler is pinned into the pipeline layout instead
+    /// of being bound per draw, inferred from `name`'s `_sampler_xyz` suffix (see
+    /// [SamplerDescription::from_binding_name_suffix]).
+    ///
+    /// Panics if `name` doesn't end with a recognized `_sampler_xyz` suffix.
+    pub fn immutable_sampler(
+        &mut self,
+        set: u32,
+        binding: u32,
+        name: &'a str,
+        stage_flags: ShaderStageFlags,
+    ) -> &mut Self {
+        let sampler = SamplerDescription::from_binding_name_suffix(name).unwrap_or_else(|| {
+            panic!(
+                "binding name \"{}\" does not match the _sampler_xyz naming convention",
+                name
+            )
+        });
+        self.descriptors.push(ResourceBinding {
+            set,
+            binding,
+            name,
+            descriptor_type: DescriptorType::SampledImage,
+            count: 1,
+            stage_flags,
+            immutable_sampler: Some(sampler),
+        });
+        self
+    }
     pub fn vertex_input(&mut self, vi: VertexInputBinding<'a>) -> &mut Self {
         self.is_root_vertex_input_signature = true;
         self.vertex_inputs.push(vi);
@@ -974,8 +1088,8 @@ impl<'a, B: Backend> DynamicSignatureBuilder<'a, B> {
         self
     }
 
-    // not good: borrows builder, result cannot be stored in a struct
-    // dynamicsignature should own stuff (box? Rc?)
+    /// Builds a signature borrowed from `arena` for the arena's `'a` lifetime; see
+    /// [DynamicSignatureBuilder::build_owned] for a version that isn't arena-bound.
     pub fn build(&self, arena: &'a Arena<B>) -> DynamicSignature<'a, B> {
         let inherited = arena.misc.alloc_extend(self.inherited.iter().cloned());
         let descriptors = arena.misc.alloc_extend(self.descriptors.iter().cloned());
@@ -1000,6 +1114,79 @@ impl<'a, B: Backend> DynamicSignatureBuilder<'a, B> {
 
         DynamicSignature { description, raw }
     }
+
+    /// Builds an owned, reference-counted [OwnedSignature] instead of one borrowed from `arena`
+    /// for its `'a` lifetime.
+    ///
+    /// `arena` is only used to reach the backend [Instance](crate::Instance) that creates and
+    /// (eventually) destroys the standalone backend signature object; the result doesn't borrow
+    /// from it.
+    ///
+    /// Unlike [build](Self::build), this deep-copies the inherited descriptions and
+    /// descriptor/binding data into the returned `OwnedSignature` rather than borrowing them, so
+    /// there's no lifetime contract for the caller to uphold: the copies live exactly as long as
+    /// the `OwnedSignature` (and its clones) do, and are freed when the last one is dropped.
+    pub fn build_owned(&self, arena: &Arena<B>) -> OwnedSignature<B> {
+        let instance = arena.instance();
+
+        let inherited: Box<[&'a SignatureDescription<'a>]> =
+            self.inherited.iter().cloned().collect();
+        let descriptors: Box<[ResourceBinding<'a>]> = self.descriptors.iter().cloned().collect();
+        let vertex_inputs: Box<[VertexInputBinding<'a>]> =
+            self.vertex_inputs.iter().cloned().collect();
+        let fragment_outputs: Box<[FragmentOutputDescription]> =
+            self.fragment_outputs.iter().cloned().collect();
+
+        // SAFETY: `description` borrows `inherited`/`descriptors`/`vertex_inputs`/
+        // `fragment_outputs` for `'static`, but those boxes are stored alongside it in the same
+        // `OwnedSignatureInner` and never reallocated (a `Box`'s heap allocation doesn't move even
+        // as the `Box` itself does), so the borrow is valid for as long as that `Inner` is alive.
+        // `Drop for OwnedSignatureInner` drops `description` before the boxes it borrows from.
+        let description: Box<SignatureDescription<'static>> = Box::new(SignatureDescription {
+            inherited: unsafe {
+                mem::transmute::<
+                    &[&'a SignatureDescription<'a>],
+                    &'static [&'static SignatureDescription<'static>],
+                >(&inherited)
+            },
+            descriptors: unsafe {
+                mem::transmute::<&[ResourceBinding<'a>], &'static [ResourceBinding<'static>]>(
+                    &descriptors,
+                )
+            },
+            vertex_inputs: unsafe {
+                mem::transmute::<&[VertexInputBinding<'a>], &'static [VertexInputBinding<'static>]>(
+                    &vertex_inputs,
+                )
+            },
+            fragment_outputs: unsafe {
+                mem::transmute::<&[FragmentOutputDescription], &'static [FragmentOutputDescription]>(
+                    &fragment_outputs,
+                )
+            },
+            depth_stencil_fragment_output: self.depth_stencil_fragment_output,
+            index_format: self.index_format,
+            num_viewports: self.num_viewports,
+            num_scissors: self.num_scissors,
+            is_root_fragment_output_signature: self.is_root_fragment_output_signature,
+            is_root_vertex_input_signature: self.is_root_vertex_input_signature,
+        });
+
+        let raw =
+            unsafe { instance.create_owned_signature(&self.inherited_signatures, &description) };
+
+        OwnedSignature {
+            inner: Rc::new(OwnedSignatureInner {
+                instance: instance as *const B::Instance,
+                description,
+                _inherited: inherited,
+                _descriptors: descriptors,
+                _vertex_inputs: vertex_inputs,
+                _fragment_outputs: fragment_outputs,
+                raw: Some(raw),
+            }),
+        }
+    }
 }
 
 impl<'a, B: Backend> Signature<'a, B> for DynamicSignature<'a, B> {
@@ -1012,70 +1199,164 @@ impl<'a, B: Backend> Signature<'a, B> for DynamicSignature<'a, B> {
     }
 }
 
-/// FIXME we are filling Vecs and option when we could be filling descriptors directly in the
-/// allocated space by the backend.
-/// This is because the current interface needs all params at the same time.
-/// Maybe a slightly less safe approach would be better here.
-/// (trait ArgBlock in backend: methods to set a parameter slot + finalize)
+/// An owned, reference-counted pipeline signature.
+///
+/// Unlike [DynamicSignature], which borrows its backend signature from an [Arena] for the arena's
+/// `'a` lifetime, `OwnedSignature` isn't tied to any allocator's lifetime: it can be cloned
+/// cheaply and stored in long-lived structs, and dropping its last clone releases the backend
+/// signature exactly once. Build one with [DynamicSignatureBuilder::build_owned].
+pub struct OwnedSignature<B: Backend> {
+    inner: Rc<OwnedSignatureInner<B>>,
+}
+
+struct OwnedSignatureInner<B: Backend> {
+    instance: *const B::Instance,
+    description: Box<SignatureDescription<'static>>,
+    // Backing storage for the slices `description` borrows (see the SAFETY comment in
+    // `build_owned`). Never read directly; kept alive only so `description` stays valid, and
+    // dropped after it.
+    _inherited: Box<[&'static SignatureDescription<'static>]>,
+    _descriptors: Box<[ResourceBinding<'static>]>,
+    _vertex_inputs: Box<[VertexInputBinding<'static>]>,
+    _fragment_outputs: Box<[FragmentOutputDescription]>,
+    raw: Option<Box<B::Signature>>,
+}
+
+impl<B: Backend> Drop for OwnedSignatureInner<B> {
+    fn drop(&mut self) {
+        unsafe {
+            (*self.instance).drop_owned_signature(self.raw.take().unwrap());
+        }
+    }
+}
+
+impl<B: Backend> Clone for OwnedSignature<B> {
+    fn clone(&self) -> Self {
+        OwnedSignature {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<B: Backend> Debug for OwnedSignature<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSignature").finish()
+    }
+}
+
+impl<'a, B: Backend> Signature<'a, B> for OwnedSignature<B> {
+    fn inner(&self) -> &'a B::Signature {
+        // SAFETY: the backend signature object is heap-allocated and kept alive by the `Rc` for
+        // as long as any clone of this `OwnedSignature` exists; callers of `Signature::inner` are
+        // expected to keep one alive for at least `'a`, the same contract `DynamicSignature`
+        // relies on for its arena.
+        unsafe { &*(self.inner.raw.as_ref().unwrap().as_ref() as *const B::Signature) }
+    }
+
+    fn description(&self) -> &SignatureDescription {
+        &self.inner.description
+    }
+}
+
+/// Incrementally assembles an argument block.
 ///
-/// Should this implement IntoArgumentBlock?
+/// Each setter immediately forwards its slot to the backend's [ArgBlock] (obtained up front from
+/// [Arena::create_arg_block_builder]) instead of buffering it here, so the backend can write
+/// descriptors straight into its own allocated/mapped memory. Only enough bookkeeping is kept
+/// around to validate the finished block against its signature (see
+/// [validate::validate_dynamic_argument_block]).
 pub struct DynamicArgumentBlockBuilder<'a, B: Backend> {
     signature: DynamicSignature<'a, B>,
-    inherited: Vec<BareArgumentBlock<'a, B>>,
-    descriptors: Vec<Descriptor<'a, B>>,
-    vertex_buffers: Vec<VertexBufferView<'a, B>>,
-    index_buffer: Option<IndexBufferView<'a, B>>,
-    render_targets: Vec<RenderTargetView<'a, B>>,
-    depth_stencil_target: Option<DepthStencilView<'a, B>>,
-    viewports: Vec<Viewport>,
-    scissors: Vec<Scissor>,
+    inner: Box<dyn ArgBlock<'a, B> + 'a>,
+    num_inherited: usize,
+    descriptor_types: Vec<DescriptorType>,
+    num_vertex_buffers: usize,
+    has_index_buffer: bool,
+    num_render_targets: usize,
+    has_depth_stencil_target: bool,
+    num_viewports: usize,
+    num_scissors: usize,
 }
 
 impl<'a, B: Backend> DynamicArgumentBlockBuilder<'a, B> {
-    pub fn new(signature: DynamicSignature<'a, B>) -> DynamicArgumentBlockBuilder<'a, B> {
+    pub fn new(
+        arena: &'a Arena<B>,
+        signature: DynamicSignature<'a, B>,
+    ) -> DynamicArgumentBlockBuilder<'a, B> {
         DynamicArgumentBlockBuilder {
+            inner: arena.create_arg_block_builder(signature),
             signature,
-            inherited: Vec::new(),
-            descriptors: Vec::new(),
-            vertex_buffers: Vec::new(),
-            index_buffer: None,
-            render_targets: Vec::new(),
-            depth_stencil_target: None,
-            viewports: Vec::new(),
-            scissors: Vec::new(),
+            num_inherited: 0,
+            descriptor_types: Vec::new(),
+            num_vertex_buffers: 0,
+            has_index_buffer: false,
+            num_render_targets: 0,
+            has_depth_stencil_target: false,
+            num_viewports: 0,
+            num_scissors: 0,
         }
     }
 
     pub fn inherited<S: Signature<'a, B>>(&mut self, args: ArgumentBlock<'a, B, S>) -> &mut Self {
-        self.inherited.push(args.into());
+        let index = self.num_inherited;
+        self.num_inherited += 1;
+        unsafe {
+            self.inner.set_inherited(index, args.into());
+        }
         self
     }
     pub fn descriptor(&mut self, d: Descriptor<'a, B>) -> &mut Self {
-        self.descriptors.push(d);
+        let index = self.descriptor_types.len();
+        self.descriptor_types.push(validate::descriptor_type_of(&d));
+        unsafe {
+            self.inner.set_descriptor(index, d);
+        }
         self
     }
     pub fn vertex_buffer<V: VertexData>(&mut self, vb: Buffer<'a, B, [V]>) -> &mut Self {
-        self.vertex_buffers.push(vb.into());
+        let index = self.num_vertex_buffers;
+        self.num_vertex_buffers += 1;
+        unsafe {
+            self.inner.set_vertex_buffer(index, vb.into());
+        }
         self
     }
     pub fn viewport(&mut self, v: Viewport) -> &mut Self {
-        self.viewports.push(v);
+        let index = self.num_viewports;
+        self.num_viewports += 1;
+        unsafe {
+            self.inner.set_viewport(index, v);
+        }
         self
     }
     pub fn scissor(&mut self, s: Scissor) -> &mut Self {
-        self.scissors.push(s);
+        let index = self.num_scissors;
+        self.num_scissors += 1;
+        unsafe {
+            self.inner.set_scissor(index, s);
+        }
         self
     }
     pub fn index_buffer<I: IndexData>(&mut self, ib: Buffer<'a, B, [I]>) -> &mut Self {
-        self.index_buffer = Some(ib.into());
+        self.has_index_buffer = true;
+        unsafe {
+            self.inner.set_index_buffer(ib.into());
+        }
         self
     }
     pub fn render_target(&mut self, rtv: RenderTargetView<'a, B>) -> &mut Self {
-        self.render_targets.push(rtv);
+        let index = self.num_render_targets;
+        self.num_render_targets += 1;
+        unsafe {
+            self.inner.set_render_target(index, rtv);
+        }
         self
     }
     pub fn depth_stencil_target(&mut self, ds: DepthStencilView<'a, B>) -> &mut Self {
-        self.depth_stencil_target = Some(ds);
+        self.has_depth_stencil_target = true;
+        unsafe {
+            self.inner.set_depth_stencil_target(ds);
+        }
         self
     }
 }
@@ -1086,20 +1367,25 @@ impl<'a, 'b, B: Backend> IntoArgumentBlock<'a, B, DynamicSignature<'a, B>>
     fn into_block(
         self,
         signature: DynamicSignature<'a, B>,
-        arena: &'a Arena<B>,
+        _arena: &'a Arena<B>,
     ) -> ArgumentBlock<'a, B, DynamicSignature<'a, B>> {
         // comparing the signatures would also work, but this is faster
         assert_eq!(signature.raw as *const _, self.signature.raw as *const _);
-        arena.create_argument_block(
+
+        #[cfg(debug_assertions)]
+        {
+            let mismatches = validate::validate_dynamic_argument_block(&self);
+            if !mismatches.is_empty() {
+                for m in &mismatches {
+                    log::error!("argument block validation error: {:?}", m);
+                }
+                panic!("argument block validation failed");
+            }
+        }
+
+        ArgumentBlock {
+            arguments: unsafe { self.inner.finalize() },
             signature,
-            self.inherited.into_iter(),
-            self.descriptors.into_iter(),
-            self.vertex_buffers.into_iter(),
-            self.index_buffer,
-            self.render_targets.into_iter(),
-            self.depth_stencil_target,
-            self.viewports.into_iter(),
-            self.scissors.into_iter(),
-        )
+        }
     }
 }
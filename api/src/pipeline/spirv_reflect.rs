@@ -0,0 +1,420 @@
+//! Automatic [ShaderStageReflection] construction from compiled SPIR-V bytecode.
+//!
+//! Walks the module's `OpDecorate`/`OpVariable`/`OpType*` instructions directly (see the
+//! [SPIR-V specification](https://www.khronos.org/registry/SPIR-V/specs/unified1/SPIRV.html#_a_id_binaryformat_a_binary_form)
+//! for the binary layout and opcode numbers used below) to recover, per stage, the descriptor
+//! bindings, vertex input attributes and fragment outputs a shader actually declares — so callers
+//! no longer have to hand-write a [DynamicSignatureBuilder] that matches the compiled shader by
+//! convention alone.
+use crate::{
+    descriptor::{DescriptorType, ResourceBinding},
+    image::SamplerDescription,
+    pipeline::{
+        DynamicSignatureBuilder, FragmentOutputDescription, ReflectedShader, ShaderStageFlags,
+        VertexInputAttributeDescription,
+    },
+    Backend,
+};
+use autograph_spirv::{PrimitiveType, TypeDesc};
+use std::collections::HashMap;
+
+const OP_NAME: u32 = 5;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_CONSTANT: u32 = 43;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+
+const DECORATION_BLOCK: u32 = 2;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_OUTPUT: u32 = 3;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+#[derive(Default)]
+struct TypeInfo {
+    opcode: u32,
+    // OpTypePointer: (storage class, pointee type id). OpTypeArray: (elem type id, id of the
+    // length constant). OpTypeVector: (component type id, component count).
+    operand_a: u32,
+    operand_b: u32,
+    decorated_block: bool,
+    decorated_buffer_block: bool,
+}
+
+#[derive(Default)]
+struct Decorations {
+    set: Option<u32>,
+    binding: Option<u32>,
+    location: Option<u32>,
+}
+
+/// A module's raw reflection data, before it is turned into [ResourceBinding]/
+/// [VertexInputAttributeDescription]/[FragmentOutputDescription] entries.
+struct Module {
+    types: HashMap<u32, TypeInfo>,
+    decorations: HashMap<u32, Decorations>,
+    names: HashMap<u32, String>,
+    // OpVariable: (id, result type id, storage class).
+    variables: Vec<(u32, u32, u32)>,
+}
+
+/// Decodes a SPIR-V literal string: UTF-8 bytes packed 4-per-word, little-endian, nul-terminated.
+fn decode_literal_string(words: &[u32]) -> String {
+    let mut bytes = Vec::with_capacity(words.len() * 4);
+    'outer: for &word in words {
+        for shift in [0, 8, 16, 24] {
+            let b = (word >> shift) as u8;
+            if b == 0 {
+                break 'outer;
+            }
+            bytes.push(b);
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn parse_words(bytecode: &[u32]) -> Module {
+    let mut module = Module {
+        types: HashMap::new(),
+        decorations: HashMap::new(),
+        names: HashMap::new(),
+        variables: Vec::new(),
+    };
+
+    assert!(
+        bytecode.len() >= 5 && bytecode[0] == 0x0723_0203,
+        "not a valid SPIR-V module"
+    );
+
+    let mut i = 5;
+    while i < bytecode.len() {
+        let word = bytecode[i];
+        let opcode = word & 0xFFFF;
+        let word_count = (word >> 16) as usize;
+        if word_count == 0 || i + word_count > bytecode.len() {
+            break;
+        }
+        let operands = &bytecode[i + 1..i + word_count];
+
+        match opcode {
+            OP_NAME => {
+                if !operands.is_empty() {
+                    module
+                        .names
+                        .insert(operands[0], decode_literal_string(&operands[1..]));
+                }
+            }
+            OP_DECORATE => {
+                if operands.len() >= 2 {
+                    let target = operands[0];
+                    let decoration = operands[1];
+                    let entry = module.decorations.entry(target).or_default();
+                    match decoration {
+                        DECORATION_DESCRIPTOR_SET => entry.set = operands.get(2).copied(),
+                        DECORATION_BINDING => entry.binding = operands.get(2).copied(),
+                        DECORATION_LOCATION => entry.location = operands.get(2).copied(),
+                        DECORATION_BLOCK => {
+                            module.types.entry(target).or_default().decorated_block = true
+                        }
+                        DECORATION_BUFFER_BLOCK => {
+                            module
+                                .types
+                                .entry(target)
+                                .or_default()
+                                .decorated_buffer_block = true
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            OP_TYPE_POINTER => {
+                if operands.len() >= 3 {
+                    let result = operands[0];
+                    let ty = module.types.entry(result).or_default();
+                    ty.opcode = OP_TYPE_POINTER;
+                    ty.operand_a = operands[1]; // storage class
+                    ty.operand_b = operands[2]; // pointee type
+                }
+            }
+            OP_TYPE_ARRAY => {
+                if operands.len() >= 3 {
+                    let result = operands[0];
+                    let ty = module.types.entry(result).or_default();
+                    ty.opcode = OP_TYPE_ARRAY;
+                    ty.operand_a = operands[1]; // element type
+                    ty.operand_b = operands[2]; // id of the OpConstant holding the length
+                }
+            }
+            OP_TYPE_VECTOR => {
+                if operands.len() >= 3 {
+                    let result = operands[0];
+                    let ty = module.types.entry(result).or_default();
+                    ty.opcode = OP_TYPE_VECTOR;
+                    ty.operand_a = operands[1]; // component type
+                    ty.operand_b = operands[2]; // component count
+                }
+            }
+            OP_TYPE_STRUCT
+            | OP_TYPE_IMAGE
+            | OP_TYPE_SAMPLER
+            | OP_TYPE_SAMPLED_IMAGE
+            | OP_TYPE_FLOAT
+            | OP_TYPE_INT => {
+                if !operands.is_empty() {
+                    let result = operands[0];
+                    module.types.entry(result).or_default().opcode = opcode;
+                }
+            }
+            OP_CONSTANT => {
+                if operands.len() >= 3 {
+                    let result = operands[1];
+                    module.types.entry(result).or_default().operand_a = operands[2];
+                }
+            }
+            OP_VARIABLE => {
+                if operands.len() >= 3 {
+                    let result_type = operands[0];
+                    let result = operands[1];
+                    let storage_class = operands[2];
+                    module.variables.push((result, result_type, storage_class));
+                }
+            }
+            OP_ENTRY_POINT => {}
+            _ => {}
+        }
+
+        i += word_count;
+    }
+
+    module
+}
+
+fn array_length(module: &Module, array_type_id: u32) -> u32 {
+    let len_const_id = module
+        .types
+        .get(&array_type_id)
+        .map(|t| t.operand_b)
+        .unwrap_or(0);
+    module
+        .types
+        .get(&len_const_id)
+        .map(|t| t.operand_a)
+        .unwrap_or(1)
+        .max(1)
+}
+
+fn descriptor_type_of(
+    module: &Module,
+    pointee_type_id: u32,
+    storage_class: u32,
+) -> Option<(DescriptorType, u32)> {
+    let (base_type_id, count) = match module.types.get(&pointee_type_id) {
+        Some(t) if t.opcode == OP_TYPE_ARRAY => {
+            (t.operand_a, array_length(module, pointee_type_id))
+        }
+        _ => (pointee_type_id, 1),
+    };
+    let base = module.types.get(&base_type_id)?;
+    let descriptor_type = match base.opcode {
+        OP_TYPE_SAMPLER => DescriptorType::Sampler,
+        OP_TYPE_SAMPLED_IMAGE => DescriptorType::SampledImage,
+        OP_TYPE_IMAGE => {
+            if storage_class == STORAGE_CLASS_UNIFORM_CONSTANT {
+                DescriptorType::StorageImage
+            } else {
+                return None;
+            }
+        }
+        OP_TYPE_STRUCT => {
+            if base.decorated_buffer_block || storage_class == STORAGE_CLASS_STORAGE_BUFFER {
+                DescriptorType::StorageBuffer
+            } else if base.decorated_block || storage_class == STORAGE_CLASS_UNIFORM {
+                DescriptorType::UniformBuffer
+            } else {
+                return None;
+            }
+        }
+        _ => return None,
+    };
+    Some((descriptor_type, count))
+}
+
+/// Reflects the descriptor-set/binding interface of a single SPIR-V stage.
+pub fn reflect_descriptors<'a>(
+    arena: &'a autograph_spirv::DroplessArena,
+    bytecode: &[u32],
+    stage: ShaderStageFlags,
+) -> &'a [ResourceBinding<'a>] {
+    let module = parse_words(bytecode);
+    let mut bindings = Vec::new();
+
+    for &(id, result_type, storage_class) in &module.variables {
+        if storage_class != STORAGE_CLASS_UNIFORM
+            && storage_class != STORAGE_CLASS_UNIFORM_CONSTANT
+            && storage_class != STORAGE_CLASS_STORAGE_BUFFER
+        {
+            continue;
+        }
+        let decorations = match module.decorations.get(&id) {
+            Some(d) => d,
+            None => continue,
+        };
+        let (set, binding) = match (decorations.set, decorations.binding) {
+            (Some(set), Some(binding)) => (set, binding),
+            _ => continue,
+        };
+        let pointee = match module.types.get(&result_type) {
+            Some(t) if t.opcode == OP_TYPE_POINTER => t.operand_b,
+            _ => continue,
+        };
+        if let Some((descriptor_type, count)) = descriptor_type_of(&module, pointee, storage_class)
+        {
+            let name = arena.alloc_str(module.names.get(&id).map(String::as_str).unwrap_or(""));
+            let immutable_sampler = if descriptor_type == DescriptorType::SampledImage {
+                SamplerDescription::from_binding_name_suffix(name)
+            } else {
+                None
+            };
+            bindings.push(ResourceBinding {
+                set,
+                binding,
+                name,
+                descriptor_type,
+                count,
+                stage_flags: stage,
+                immutable_sampler,
+            });
+        }
+    }
+
+    arena.alloc_extend(bindings)
+}
+
+/// Reflects the vertex input attributes of a vertex-stage SPIR-V module (`Input` storage class
+/// variables, keyed by their `Location` decoration).
+pub fn reflect_vertex_input_attributes<'a>(
+    arena: &'a autograph_spirv::DroplessArena,
+    bytecode: &[u32],
+) -> &'a [VertexInputAttributeDescription<'a>] {
+    let module = parse_words(bytecode);
+    let mut attributes = Vec::new();
+
+    for &(id, result_type, storage_class) in &module.variables {
+        if storage_class != STORAGE_CLASS_INPUT {
+            continue;
+        }
+        let location = module.decorations.get(&id).and_then(|d| d.location);
+        let pointee = match module.types.get(&result_type) {
+            Some(t) if t.opcode == OP_TYPE_POINTER => t.operand_b,
+            _ => continue,
+        };
+        let ty = arena.alloc(spirv_type_to_typedesc(&module, pointee));
+        attributes.push(VertexInputAttributeDescription {
+            location,
+            ty,
+            semantic: None,
+        });
+    }
+
+    arena.alloc_extend(attributes)
+}
+
+fn spirv_type_to_typedesc<'a>(module: &Module, type_id: u32) -> TypeDesc<'a> {
+    match module.types.get(&type_id) {
+        Some(t) if t.opcode == OP_TYPE_FLOAT => TypeDesc::Primitive(PrimitiveType::Float),
+        Some(t) if t.opcode == OP_TYPE_INT => TypeDesc::Primitive(PrimitiveType::Int),
+        Some(t) if t.opcode == OP_TYPE_VECTOR => {
+            let elem = spirv_type_to_typedesc(module, t.operand_a);
+            let len = t.operand_b as u8;
+            match elem {
+                TypeDesc::Primitive(p) => TypeDesc::Vector(p, len),
+                _ => TypeDesc::Vector(PrimitiveType::Float, len),
+            }
+        }
+        _ => TypeDesc::Primitive(PrimitiveType::Float),
+    }
+}
+
+/// Counts the `Output` storage-class variables of a fragment-stage SPIR-V module into
+/// [FragmentOutputDescription] entries (one per output, in no particular resolve state — see
+/// [crate::pipeline::AttachmentLayout::resolve_attachments] for how resolves are later attached).
+pub fn reflect_fragment_outputs(bytecode: &[u32]) -> Vec<FragmentOutputDescription> {
+    let module = parse_words(bytecode);
+    module
+        .variables
+        .iter()
+        .filter(|&&(_, _, storage_class)| storage_class == STORAGE_CLASS_OUTPUT)
+        .map(|_| FragmentOutputDescription { resolve: false })
+        .collect()
+}
+
+/// Fuses the reflected descriptor bindings of several shader stages into one
+/// [DynamicSignatureBuilder], ORing `stage_flags` together where two stages share a (set,
+/// binding) slot and panicking if they disagree on the slot's `descriptor_type` or `count`.
+pub fn from_reflected_stages<'a, B: Backend>(
+    stages: &[ReflectedShader<'_, 'a>],
+) -> DynamicSignatureBuilder<'a, B> {
+    let mut builder = DynamicSignatureBuilder::new();
+    let mut fused: HashMap<(u32, u32), ResourceBinding<'a>> = HashMap::new();
+
+    for stage in stages {
+        for d in stage.reflection.descriptors {
+            fused
+                .entry((d.set, d.binding))
+                .and_modify(|existing| {
+                    if existing.descriptor_type != d.descriptor_type || existing.count != d.count
+                    {
+                        panic!(
+                            "conflicting descriptor declarations at set {} binding {}: {:?}x{} vs {:?}x{}",
+                            d.set,
+                            d.binding,
+                            existing.descriptor_type,
+                            existing.count,
+                            d.descriptor_type,
+                            d.count
+                        );
+                    }
+                    existing.stage_flags |= d.stage_flags;
+                })
+                .or_insert(*d);
+        }
+        // Vertex input attributes ([VertexInputAttributeDescription], from
+        // `reflect_vertex_input_attributes`) describe the vertex stage's interface for display
+        // purposes; building the [crate::vertex::VertexInputBinding] the signature actually
+        // stores requires the caller's buffer layout (stride, per-attribute offsets) and so is
+        // still supplied by hand via [DynamicSignatureBuilder::vertex_input].
+        if stage.reflection.stage.contains(ShaderStageFlags::FRAGMENT) {
+            for &frag in stage.reflection.fragment_outputs {
+                builder.fragment_output(frag);
+            }
+        }
+    }
+
+    // `fused` is a `HashMap`, whose iteration order isn't deterministic; sort by `(set, binding)`
+    // before feeding the builder so two reflections of the same stages always produce the same
+    // descriptor order, which matters for anything downstream that hashes or caches the resulting
+    // `SignatureDescription`.
+    let mut fused: Vec<((u32, u32), ResourceBinding<'a>)> = fused.into_iter().collect();
+    fused.sort_by_key(|&(key, _)| key);
+
+    for (_, d) in fused {
+        builder.descriptor(d);
+    }
+
+    builder
+}
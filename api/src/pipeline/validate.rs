@@ -0,0 +1,244 @@
+//! Validation passes run over pipeline descriptions before they are handed to the backend.
+use crate::{
+    descriptor::{Descriptor, DescriptorType},
+    pipeline::{
+        AttachmentLayout, DynamicArgumentBlockBuilder, GraphicsPipelineCreateInfo,
+        PrimitiveTopology, Signature, SignatureDescription,
+    },
+    Backend,
+};
+
+/// Checks that `description` is suitable for a compute pipeline: no vertex inputs and no
+/// fragment outputs, since compute dispatches don't go through the rasterizer.
+///
+/// Returns a list of human-readable errors; empty if the signature is valid.
+pub fn validate_compute_pipeline_signature(description: &SignatureDescription) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if description.is_root_vertex_input_signature || !description.vertex_inputs.is_empty() {
+        errors.push("compute pipeline signature must not declare vertex inputs".to_string());
+    }
+
+    if description.is_root_fragment_output_signature
+        || !description.fragment_outputs.is_empty()
+        || description.depth_stencil_fragment_output.is_some()
+    {
+        errors.push("compute pipeline signature must not declare fragment outputs".to_string());
+    }
+
+    errors
+}
+
+/// Checks that `layout`'s resolve attachments (if any) are well-formed: one entry per color
+/// attachment, each resolve target's format matching its source and `samples == 1` while the
+/// source has `samples > 1`.
+pub fn validate_resolve_attachments(layout: &AttachmentLayout) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if layout.resolve_attachments.is_empty() {
+        return errors;
+    }
+
+    if layout.resolve_attachments.len() != layout.color_attachments.len() {
+        errors.push(format!(
+            "resolve_attachments has {} entries but color_attachments has {}",
+            layout.resolve_attachments.len(),
+            layout.color_attachments.len()
+        ));
+        return errors;
+    }
+
+    for (i, (color, resolve)) in layout
+        .color_attachments
+        .iter()
+        .zip(layout.resolve_attachments.iter())
+        .enumerate()
+    {
+        if color.samples <= 1 {
+            // Nothing to resolve; the corresponding resolve entry is unused.
+            continue;
+        }
+        if resolve.samples != 1 {
+            errors.push(format!(
+                "resolve attachment {} must have samples == 1, got {}",
+                i, resolve.samples
+            ));
+        }
+        if resolve.format != color.format {
+            errors.push(format!(
+                "resolve attachment {} format {:?} does not match source color attachment format {:?}",
+                i, resolve.format, color.format
+            ));
+        }
+    }
+
+    errors
+}
+
+/// One slot where a built [DynamicArgumentBlockBuilder] disagrees with the
+/// [SignatureDescription] it's being built against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ArgumentBlockMismatch {
+    DescriptorCount {
+        expected: usize,
+        found: usize,
+    },
+    DescriptorType {
+        slot: usize,
+        expected: DescriptorType,
+        found: DescriptorType,
+    },
+    VertexBufferCount {
+        expected: usize,
+        found: usize,
+    },
+    IndexBufferPresence {
+        expected: bool,
+        found: bool,
+    },
+    RenderTargetCount {
+        expected: usize,
+        found: usize,
+    },
+    DepthStencilTargetPresence {
+        expected: bool,
+        found: bool,
+    },
+    ViewportCount {
+        expected: usize,
+        found: usize,
+    },
+    ScissorCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+pub(crate) fn descriptor_type_of<'a, B: Backend>(d: &Descriptor<'a, B>) -> DescriptorType {
+    match d {
+        Descriptor::UniformBuffer { .. } => DescriptorType::UniformBuffer,
+        Descriptor::StorageBuffer { .. } => DescriptorType::StorageBuffer,
+        Descriptor::SampledImage { .. } => DescriptorType::SampledImage,
+        Descriptor::StorageImage { .. } => DescriptorType::StorageImage,
+    }
+}
+
+/// Checks a [DynamicArgumentBlockBuilder] against its signature before it is turned into an
+/// [crate::pipeline::ArgumentBlock]: descriptor count and per-slot type, vertex buffer/index
+/// buffer/render target/viewport/scissor counts and presence.
+///
+/// Returns every mismatch found rather than stopping at the first one, so tooling (or a debug-only
+/// assertion at `into_block` time) can report the whole picture at once.
+///
+/// FIXME: per-attribute vertex buffer stride/format compatibility against the declared
+/// `VertexInputBinding` layout is not checked yet — it requires the vertex buffer views to carry
+/// their source format/stride, which they don't expose today.
+pub fn validate_dynamic_argument_block<'a, B: Backend>(
+    builder: &DynamicArgumentBlockBuilder<'a, B>,
+) -> Vec<ArgumentBlockMismatch> {
+    let mut errors = Vec::new();
+    let description = builder.signature.description();
+
+    if builder.descriptor_types.len() != description.descriptors.len() {
+        errors.push(ArgumentBlockMismatch::DescriptorCount {
+            expected: description.descriptors.len(),
+            found: builder.descriptor_types.len(),
+        });
+    }
+    for (slot, (expected, &found)) in description
+        .descriptors
+        .iter()
+        .zip(builder.descriptor_types.iter())
+        .enumerate()
+    {
+        if expected.descriptor_type != found {
+            errors.push(ArgumentBlockMismatch::DescriptorType {
+                slot,
+                expected: expected.descriptor_type,
+                found,
+            });
+        }
+    }
+
+    if builder.num_vertex_buffers != description.vertex_inputs.len() {
+        errors.push(ArgumentBlockMismatch::VertexBufferCount {
+            expected: description.vertex_inputs.len(),
+            found: builder.num_vertex_buffers,
+        });
+    }
+
+    if builder.has_index_buffer != description.index_format.is_some() {
+        errors.push(ArgumentBlockMismatch::IndexBufferPresence {
+            expected: description.index_format.is_some(),
+            found: builder.has_index_buffer,
+        });
+    }
+
+    if builder.num_render_targets != description.fragment_outputs.len() {
+        errors.push(ArgumentBlockMismatch::RenderTargetCount {
+            expected: description.fragment_outputs.len(),
+            found: builder.num_render_targets,
+        });
+    }
+
+    if builder.has_depth_stencil_target != description.depth_stencil_fragment_output.is_some() {
+        errors.push(ArgumentBlockMismatch::DepthStencilTargetPresence {
+            expected: description.depth_stencil_fragment_output.is_some(),
+            found: builder.has_depth_stencil_target,
+        });
+    }
+
+    if builder.num_viewports != description.num_viewports {
+        errors.push(ArgumentBlockMismatch::ViewportCount {
+            expected: description.num_viewports,
+            found: builder.num_viewports,
+        });
+    }
+
+    if builder.num_scissors != description.num_scissors {
+        errors.push(ArgumentBlockMismatch::ScissorCount {
+            expected: description.num_scissors,
+            found: builder.num_scissors,
+        });
+    }
+
+    errors
+}
+
+/// Checks that `create_info`'s tessellation state agrees with its shader stages and topology:
+/// `tess_control`/`tess_eval` require `PrimitiveTopology::PatchList` and a non-zero
+/// `tessellation_state.patch_control_points`, and vice versa.
+pub fn validate_tessellation_state<B: Backend>(
+    create_info: &GraphicsPipelineCreateInfo<'_, '_, B>,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    let has_tess_shaders = create_info.shader_stages.tess_control.is_some()
+        || create_info.shader_stages.tess_eval.is_some();
+    let is_patch_list = create_info.input_assembly_state.topology == PrimitiveTopology::PatchList;
+    let patch_control_points = create_info.tessellation_state.patch_control_points;
+
+    if has_tess_shaders {
+        if !is_patch_list {
+            errors.push(
+                "pipeline has tess_control/tess_eval shaders but input_assembly_state.topology \
+                 is not PrimitiveTopology::PatchList"
+                    .to_string(),
+            );
+        }
+        if patch_control_points == 0 {
+            errors.push(
+                "pipeline has tess_control/tess_eval shaders but tessellation_state.patch_control_points is 0"
+                    .to_string(),
+            );
+        }
+    } else if is_patch_list {
+        errors.push(
+            "input_assembly_state.topology is PrimitiveTopology::PatchList but pipeline has no \
+             tess_control/tess_eval shaders"
+                .to_string(),
+        );
+    }
+
+    errors
+}
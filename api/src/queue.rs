@@ -0,0 +1,86 @@
+//! Multi-queue submission: splitting a sorted command stream into one sub-stream per [Queue] and
+//! deriving the cross-queue waits needed wherever a resource written on one queue is read on
+//! another, reusing [crate::sync]'s per-resource access tracking instead of having callers specify
+//! the dependency edges themselves.
+//!
+//! Like [crate::sync] and [crate::alias], [split_queues] is generic over an already-extracted
+//! per-command `(Queue, Vec<ResourceAccess>)` pair, not over [Command](crate::command::Command)
+//! itself: see the FIXME on [Instance::submit_queue](crate::Instance::submit_queue) for why.
+use crate::sync::{ResourceAccess, ResourceKey};
+use crate::Queue;
+use std::collections::HashMap;
+
+/// A wait a queue's sub-stream must perform, immediately before the command at `before_index` (an
+/// index into that same queue's `command_indices`, i.e. local to its own sub-stream), on another
+/// queue's timeline semaphore.
+///
+/// Modeled on Vulkan timeline semaphores: a queue's `n`th submitted command, once it executes, is
+/// considered to signal timeline value `n + 1` on that queue, so waiting for a value is waiting for
+/// that many of the source queue's commands to have completed.
+#[derive(Copy, Clone, Debug)]
+pub struct QueueWait {
+    pub before_index: usize,
+    pub wait_queue: Queue,
+    pub wait_value: u64,
+}
+
+/// One [Queue]'s sub-stream, as split out by [split_queues]: the indices into the original sorted
+/// command list assigned to it, in order, plus the [QueueWait]s that must be inserted before
+/// specific commands in it.
+#[derive(Clone, Debug, Default)]
+pub struct QueueSubmission {
+    pub command_indices: Vec<usize>,
+    pub waits: Vec<QueueWait>,
+}
+
+/// Splits a sorted command stream into one [QueueSubmission] per [Queue] referenced by
+/// `queue_per_command`, and derives the [QueueWait]s needed so a resource written on one queue is
+/// never read (or written) on another queue before that write has actually executed there.
+///
+/// `queue_per_command` and `accesses_per_command` are parallel to the original sorted command
+/// list: `queue_per_command[i]` is the queue command `i` targets, `accesses_per_command[i]` is the
+/// resources it reads/writes (see [ResourceAccess], as already consumed by
+/// [crate::sync::derive_barriers]). Same-queue barriers between those accesses are still
+/// `derive_barriers`'s job; this only adds the waits needed *across* queues, since a queue can't
+/// rely on in-order execution to order it after work submitted to a different queue.
+pub fn split_queues(
+    queue_per_command: &[Queue],
+    accesses_per_command: &[Vec<ResourceAccess>],
+) -> HashMap<Queue, QueueSubmission> {
+    assert_eq!(queue_per_command.len(), accesses_per_command.len());
+
+    let mut submissions: HashMap<Queue, QueueSubmission> = HashMap::new();
+    // The last queue and timeline value to access each resource, so a later access from a
+    // different queue knows which value to wait on.
+    let mut last_access: HashMap<ResourceKey, (Queue, u64)> = HashMap::new();
+
+    for (command_index, (&queue, accesses)) in queue_per_command
+        .iter()
+        .zip(accesses_per_command)
+        .enumerate()
+    {
+        let local_index = submissions.entry(queue).or_default().command_indices.len();
+        let local_value = local_index as u64 + 1;
+
+        for access in accesses {
+            if let Some(&(src_queue, src_value)) = last_access.get(&access.key) {
+                if src_queue != queue {
+                    submissions.entry(queue).or_default().waits.push(QueueWait {
+                        before_index: local_index,
+                        wait_queue: src_queue,
+                        wait_value: src_value,
+                    });
+                }
+            }
+            last_access.insert(access.key, (queue, local_value));
+        }
+
+        submissions
+            .entry(queue)
+            .or_default()
+            .command_indices
+            .push(command_index);
+    }
+
+    submissions
+}
@@ -0,0 +1,44 @@
+//! Host-visible readback buffers and the fence-driven callback queue that maps them once the GPU
+//! work that writes to them has finished, modeled on wgpu's `mapAsync`.
+//!
+//! FIXME: the actual GPU-side copy into a [HostReadback]'s buffer can't be expressed as a real
+//! `Command` variant yet — `command.rs` isn't present in this crate snapshot (see the FIXME on
+//! [Instance::derive_frame_barriers](crate::Instance::derive_frame_barriers) for the same gap).
+//! Once it exists, give `Command` a variant that copies an image or buffer region into a
+//! [HostReadback]'s [buffer](HostReadback::buffer) (mirroring `CommandInner::CopyBufferToImage` in
+//! the sibling `render` crate's `cmd` module), and register it as an `AccessType::TransferWrite`
+//! for [crate::sync::derive_barriers].
+use crate::{Backend, BufferTypeless, Instance};
+
+/// A host-visible, host-readable buffer that commands copy GPU data into, and that the CPU can
+/// later map for reading once the GPU work that wrote it has finished executing.
+///
+/// Created with [Arena::create_readback_buffer](crate::Arena::create_readback_buffer).
+pub struct HostReadback<'a, B: Backend> {
+    pub(crate) instance: &'a B::Instance,
+    pub(crate) raw: &'a B::Buffer,
+    pub(crate) size: u64,
+}
+
+impl<'a, B: Backend> HostReadback<'a, B> {
+    /// The untyped buffer commands should copy GPU data into.
+    pub fn buffer(&self) -> BufferTypeless<B> {
+        BufferTypeless(self.raw)
+    }
+
+    /// The buffer's size in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Registers `callback` to run, with a `&[u8]` view of this buffer's mapped memory, once the
+    /// GPU work that wrote to it has finished executing. Does not block: call
+    /// [Instance::poll](crate::Instance::poll) (typically once per frame, as part of the normal
+    /// frame loop) to actually drive pending callbacks.
+    pub fn map_async(&self, callback: impl FnOnce(&[u8]) + Send + 'static) {
+        unsafe {
+            self.instance
+                .map_readback_buffer_async(self.raw, Box::new(callback))
+        }
+    }
+}
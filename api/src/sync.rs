@@ -0,0 +1,410 @@
+//! Automatic GPU synchronization: deriving the barriers between commands in a sorted command
+//! stream from a vk-sync-style table of named [AccessType]s, instead of backends each
+//! reimplementing their own hazard tracking.
+//!
+//! [derive_barriers] (and its incremental counterpart, [SyncCommandBuffer], for backends that
+//! record one command at a time) is generic over the tracked resource's backend type but not over
+//! [Command](crate::command::Command) itself: see the FIXME on [Instance::derive_frame_barriers]
+//! for why.
+//!
+//! [Instance::derive_frame_barriers]: crate::Instance::derive_frame_barriers
+use crate::Backend;
+use bitflags::bitflags;
+use std::collections::HashMap;
+
+bitflags! {
+    /// Pipeline stages a barrier can wait on (`src`) or block (`dst`), mirroring Vulkan's
+    /// `VkPipelineStageFlags`.
+    pub struct PipelineStageMask: u32 {
+        const TOP_OF_PIPE = 0b0000_0000_0001;
+        const DRAW_INDIRECT = 0b0000_0000_0010;
+        const VERTEX_INPUT = 0b0000_0000_0100;
+        const VERTEX_SHADER = 0b0000_0000_1000;
+        const FRAGMENT_SHADER = 0b0000_0001_0000;
+        const COMPUTE_SHADER = 0b0000_0010_0000;
+        const COLOR_ATTACHMENT_OUTPUT = 0b0000_0100_0000;
+        const EARLY_FRAGMENT_TESTS = 0b0000_1000_0000;
+        const LATE_FRAGMENT_TESTS = 0b0001_0000_0000;
+        const TRANSFER = 0b0010_0000_0000;
+        const BOTTOM_OF_PIPE = 0b0100_0000_0000;
+        const ALL_COMMANDS = 0b1000_0000_0000;
+    }
+}
+
+bitflags! {
+    /// Memory-access flags a barrier synchronizes, mirroring Vulkan's `VkAccessFlags`.
+    pub struct AccessMask: u32 {
+        const INDIRECT_COMMAND_READ = 0b0000_0000_0001;
+        const INDEX_READ = 0b0000_0000_0010;
+        const VERTEX_ATTRIBUTE_READ = 0b0000_0000_0100;
+        const UNIFORM_READ = 0b0000_0000_1000;
+        const SHADER_READ = 0b0000_0001_0000;
+        const SHADER_WRITE = 0b0000_0010_0000;
+        const COLOR_ATTACHMENT_READ = 0b0000_0100_0000;
+        const COLOR_ATTACHMENT_WRITE = 0b0000_1000_0000;
+        const DEPTH_STENCIL_ATTACHMENT_READ = 0b0001_0000_0000;
+        const DEPTH_STENCIL_ATTACHMENT_WRITE = 0b0010_0000_0000;
+        const TRANSFER_READ = 0b0100_0000_0000;
+        const TRANSFER_WRITE = 0b1000_0000_0000;
+    }
+}
+
+impl AccessMask {
+    /// Whether any of the write bits are set.
+    pub fn is_write(self) -> bool {
+        self.intersects(
+            AccessMask::SHADER_WRITE
+                | AccessMask::COLOR_ATTACHMENT_WRITE
+                | AccessMask::DEPTH_STENCIL_ATTACHMENT_WRITE
+                | AccessMask::TRANSFER_WRITE,
+        )
+    }
+}
+
+/// The layout an image must be in for a given access; mirrors the subset of `VkImageLayout` this
+/// crate's backends care about.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ImageLayout {
+    Undefined,
+    General,
+    ColorAttachmentOptimal,
+    DepthStencilAttachmentOptimal,
+    ShaderReadOnlyOptimal,
+    TransferSrcOptimal,
+    TransferDstOptimal,
+    PresentSrc,
+}
+
+/// The `(stage, access, layout)` triple a named [AccessType] resolves to, via [AccessType::info].
+#[derive(Copy, Clone, Debug)]
+pub struct AccessInfo {
+    pub stage: PipelineStageMask,
+    pub access: AccessMask,
+    /// `Some` for accesses to an image resource, which care about layout; `None` for accesses
+    /// that only ever apply to buffers.
+    pub layout: Option<ImageLayout>,
+}
+
+/// A named way a command can access a resource, each mapping to a static `(stage, access,
+/// layout)` triple via [AccessType::info], following the access-type table used by
+/// [vk-sync](https://github.com/gwihlidal/vk-sync-rs).
+///
+/// Callers build up a command's declared accesses from these variants rather than poking at raw
+/// stage/access masks directly, so the conflict analysis in [derive_barriers] stays correct even
+/// as new access patterns are added here.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AccessType {
+    Nothing,
+    IndirectBuffer,
+    IndexBuffer,
+    VertexBuffer,
+    VertexShaderReadUniformBuffer,
+    VertexShaderReadSampledImage,
+    FragmentShaderReadUniformBuffer,
+    FragmentShaderReadSampledImage,
+    ComputeShaderReadUniformBuffer,
+    ComputeShaderReadSampledImage,
+    ComputeShaderReadStorageBuffer,
+    ComputeShaderReadStorageImage,
+    ComputeShaderWriteStorageBuffer,
+    ComputeShaderWriteStorageImage,
+    ColorAttachmentRead,
+    ColorAttachmentWrite,
+    DepthStencilAttachmentRead,
+    DepthStencilAttachmentWrite,
+    TransferRead,
+    TransferWrite,
+    Present,
+}
+
+impl AccessType {
+    /// The static `(stage, access, layout)` triple this access type maps to.
+    pub fn info(self) -> AccessInfo {
+        use AccessType::*;
+        match self {
+            Nothing => AccessInfo {
+                stage: PipelineStageMask::empty(),
+                access: AccessMask::empty(),
+                layout: None,
+            },
+            IndirectBuffer => AccessInfo {
+                stage: PipelineStageMask::DRAW_INDIRECT,
+                access: AccessMask::INDIRECT_COMMAND_READ,
+                layout: None,
+            },
+            IndexBuffer => AccessInfo {
+                stage: PipelineStageMask::VERTEX_INPUT,
+                access: AccessMask::INDEX_READ,
+                layout: None,
+            },
+            VertexBuffer => AccessInfo {
+                stage: PipelineStageMask::VERTEX_INPUT,
+                access: AccessMask::VERTEX_ATTRIBUTE_READ,
+                layout: None,
+            },
+            VertexShaderReadUniformBuffer => AccessInfo {
+                stage: PipelineStageMask::VERTEX_SHADER,
+                access: AccessMask::UNIFORM_READ,
+                layout: None,
+            },
+            VertexShaderReadSampledImage => AccessInfo {
+                stage: PipelineStageMask::VERTEX_SHADER,
+                access: AccessMask::SHADER_READ,
+                layout: Some(ImageLayout::ShaderReadOnlyOptimal),
+            },
+            FragmentShaderReadUniformBuffer => AccessInfo {
+                stage: PipelineStageMask::FRAGMENT_SHADER,
+                access: AccessMask::UNIFORM_READ,
+                layout: None,
+            },
+            FragmentShaderReadSampledImage => AccessInfo {
+                stage: PipelineStageMask::FRAGMENT_SHADER,
+                access: AccessMask::SHADER_READ,
+                layout: Some(ImageLayout::ShaderReadOnlyOptimal),
+            },
+            ComputeShaderReadUniformBuffer => AccessInfo {
+                stage: PipelineStageMask::COMPUTE_SHADER,
+                access: AccessMask::UNIFORM_READ,
+                layout: None,
+            },
+            ComputeShaderReadSampledImage => AccessInfo {
+                stage: PipelineStageMask::COMPUTE_SHADER,
+                access: AccessMask::SHADER_READ,
+                layout: Some(ImageLayout::ShaderReadOnlyOptimal),
+            },
+            ComputeShaderReadStorageBuffer => AccessInfo {
+                stage: PipelineStageMask::COMPUTE_SHADER,
+                access: AccessMask::SHADER_READ,
+                layout: None,
+            },
+            ComputeShaderReadStorageImage => AccessInfo {
+                stage: PipelineStageMask::COMPUTE_SHADER,
+                access: AccessMask::SHADER_READ,
+                layout: Some(ImageLayout::General),
+            },
+            ComputeShaderWriteStorageBuffer => AccessInfo {
+                stage: PipelineStageMask::COMPUTE_SHADER,
+                access: AccessMask::SHADER_WRITE,
+                layout: None,
+            },
+            ComputeShaderWriteStorageImage => AccessInfo {
+                stage: PipelineStageMask::COMPUTE_SHADER,
+                access: AccessMask::SHADER_WRITE,
+                layout: Some(ImageLayout::General),
+            },
+            ColorAttachmentRead => AccessInfo {
+                stage: PipelineStageMask::COLOR_ATTACHMENT_OUTPUT,
+                access: AccessMask::COLOR_ATTACHMENT_READ,
+                layout: Some(ImageLayout::ColorAttachmentOptimal),
+            },
+            ColorAttachmentWrite => AccessInfo {
+                stage: PipelineStageMask::COLOR_ATTACHMENT_OUTPUT,
+                access: AccessMask::COLOR_ATTACHMENT_WRITE,
+                layout: Some(ImageLayout::ColorAttachmentOptimal),
+            },
+            DepthStencilAttachmentRead => AccessInfo {
+                stage: PipelineStageMask::EARLY_FRAGMENT_TESTS
+                    | PipelineStageMask::LATE_FRAGMENT_TESTS,
+                access: AccessMask::DEPTH_STENCIL_ATTACHMENT_READ,
+                layout: Some(ImageLayout::DepthStencilAttachmentOptimal),
+            },
+            DepthStencilAttachmentWrite => AccessInfo {
+                stage: PipelineStageMask::EARLY_FRAGMENT_TESTS
+                    | PipelineStageMask::LATE_FRAGMENT_TESTS,
+                access: AccessMask::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                layout: Some(ImageLayout::DepthStencilAttachmentOptimal),
+            },
+            TransferRead => AccessInfo {
+                stage: PipelineStageMask::TRANSFER,
+                access: AccessMask::TRANSFER_READ,
+                layout: Some(ImageLayout::TransferSrcOptimal),
+            },
+            TransferWrite => AccessInfo {
+                stage: PipelineStageMask::TRANSFER,
+                access: AccessMask::TRANSFER_WRITE,
+                layout: Some(ImageLayout::TransferDstOptimal),
+            },
+            Present => AccessInfo {
+                stage: PipelineStageMask::BOTTOM_OF_PIPE,
+                access: AccessMask::empty(),
+                layout: Some(ImageLayout::PresentSrc),
+            },
+        }
+    }
+
+    /// Whether this access type writes to the resource.
+    pub fn is_write(self) -> bool {
+        self.info().access.is_write()
+    }
+}
+
+/// Identifies a tracked resource by the address of its backend object, so two accesses that
+/// reference the same buffer or image (e.g. two descriptors referencing the same `&'a B::Buffer`/
+/// `&'a B::Image` handed out by an arena) resolve to the same key.
+///
+/// `B::Image`/`B::Buffer` are only required to be `Sync + Debug` (not `Eq`/`Hash`), so pointer
+/// identity is the only way to key a resource-state map over them.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ResourceKey {
+    Buffer(usize),
+    Image(usize),
+}
+
+impl ResourceKey {
+    pub fn of_image<B: Backend>(image: &B::Image) -> ResourceKey {
+        ResourceKey::Image(image as *const _ as usize)
+    }
+
+    pub fn of_buffer<B: Backend>(buffer: &B::Buffer) -> ResourceKey {
+        ResourceKey::Buffer(buffer as *const _ as usize)
+    }
+}
+
+/// One resource a command reads or writes, and how, for [derive_barriers].
+#[derive(Copy, Clone, Debug)]
+pub struct ResourceAccess {
+    pub key: ResourceKey,
+    pub access_type: AccessType,
+}
+
+/// A barrier between a resource's prior use and its next one, to be executed immediately before
+/// the command whose access conflicted with the last recorded state.
+#[derive(Copy, Clone, Debug)]
+pub struct Barrier {
+    pub resource: ResourceKey,
+    pub src_stage_mask: PipelineStageMask,
+    pub dst_stage_mask: PipelineStageMask,
+    pub src_access_mask: AccessMask,
+    pub dst_access_mask: AccessMask,
+    /// `Some` for image resources undergoing a layout transition; `None` for buffers, which have
+    /// no layout, and for images that don't need one.
+    pub old_layout: Option<ImageLayout>,
+    pub new_layout: Option<ImageLayout>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ResourceState {
+    stage: PipelineStageMask,
+    access: AccessMask,
+    layout: Option<ImageLayout>,
+}
+
+/// The shared conflict analysis behind both [derive_barriers] and [SyncCommandBuffer]: given the
+/// per-resource state recorded so far, returns the barriers `accesses` needs and updates `states`
+/// for whatever comes next.
+///
+/// A barrier is emitted for a resource access that conflicts with the recorded state of that
+/// resource: a write after a read or write, or an image access whose required layout differs from
+/// the one it's currently in. Reads that follow reads with no intervening write, and no layout
+/// mismatch, need no barrier; their stages and accesses accumulate in the recorded state, so the
+/// next write's barrier waits on all of them. Any other access replaces the recorded state with
+/// its own. A resource seen for the
+/// first time gets an initial transition out of the implicit [ImageLayout::Undefined] layout if its
+/// access cares about layout at all (buffer accesses, whose [AccessInfo::layout] is `None`, need no
+/// such transition and so generate no barrier on first access).
+fn barriers_for_accesses(
+    states: &mut HashMap<ResourceKey, ResourceState>,
+    accesses: &[ResourceAccess],
+) -> Vec<Barrier> {
+    let mut barriers = Vec::new();
+
+    for access in accesses {
+        let info = access.access_type.info();
+
+        match states.get(&access.key).copied() {
+            Some(state) => {
+                let needs_barrier = state.access.is_write()
+                    || info.access.is_write()
+                    || state.layout != info.layout;
+
+                if needs_barrier {
+                    let mut barrier = Barrier {
+                        resource: access.key,
+                        src_stage_mask: state.stage,
+                        dst_stage_mask: info.stage,
+                        src_access_mask: state.access,
+                        dst_access_mask: info.access,
+                        old_layout: None,
+                        new_layout: None,
+                    };
+                    if let (Some(old_layout), Some(new_layout)) = (state.layout, info.layout) {
+                        if old_layout != new_layout {
+                            barrier.old_layout = Some(old_layout);
+                            barrier.new_layout = Some(new_layout);
+                        }
+                    }
+                    barriers.push(barrier);
+                }
+            }
+            None => {
+                if let Some(new_layout) = info.layout {
+                    barriers.push(Barrier {
+                        resource: access.key,
+                        src_stage_mask: PipelineStageMask::TOP_OF_PIPE,
+                        dst_stage_mask: info.stage,
+                        src_access_mask: AccessMask::empty(),
+                        dst_access_mask: info.access,
+                        old_layout: Some(ImageLayout::Undefined),
+                        new_layout: Some(new_layout),
+                    });
+                }
+            }
+        }
+
+        let mut next = ResourceState {
+            stage: info.stage,
+            access: info.access,
+            layout: info.layout,
+        };
+        if let Some(state) = states.get(&access.key) {
+            // Read after read in the same layout: keep the earlier readers for the next write.
+            if !state.access.is_write() && !info.access.is_write() && state.layout == info.layout {
+                next.stage |= state.stage;
+                next.access |= state.access;
+            }
+        }
+        states.insert(access.key, next);
+    }
+
+    barriers
+}
+
+/// Walks `accesses_per_command` (one entry per command, in the same order as the already-sorted
+/// command stream it was extracted from) and, for each command, returns the barriers that must
+/// execute immediately before it: one list of barriers per command, parallel to the input.
+///
+/// See [barriers_for_accesses] for the conflict rule; this just drives it over a whole stream's
+/// worth of accesses at once. [SyncCommandBuffer] drives the same rule one command at a time, for
+/// backends that want to interleave hazard tracking with recording instead of extracting every
+/// command's accesses up front.
+pub fn derive_barriers(accesses_per_command: &[Vec<ResourceAccess>]) -> Vec<Vec<Barrier>> {
+    let mut states: HashMap<ResourceKey, ResourceState> = HashMap::new();
+    accesses_per_command
+        .iter()
+        .map(|accesses| barriers_for_accesses(&mut states, accesses))
+        .collect()
+}
+
+/// Incremental hazard tracking for backends that record a command at a time instead of collecting
+/// a whole frame's `Vec<Vec<ResourceAccess>>` up front: call [access](SyncCommandBuffer::access)
+/// once per command, in the sorted command stream's order, and it returns that command's barriers
+/// immediately, sharing [derive_barriers]'s exact conflict rules so backends no longer each
+/// reimplement their own hazard tracking.
+#[derive(Default)]
+pub struct SyncCommandBuffer {
+    states: HashMap<ResourceKey, ResourceState>,
+}
+
+impl SyncCommandBuffer {
+    pub fn new() -> SyncCommandBuffer {
+        SyncCommandBuffer {
+            states: HashMap::new(),
+        }
+    }
+
+    /// Records one command's resource accesses and returns the barriers to insert immediately
+    /// before it.
+    pub fn access(&mut self, accesses: &[ResourceAccess]) -> Vec<Barrier> {
+        barriers_for_accesses(&mut self.states, accesses)
+    }
+}
@@ -0,0 +1,69 @@
+//! Parallel, panic-isolated batch reading over many images.
+//!
+//! Useful for asset-pipeline tools that need to process a large directory of images and can't
+//! let one corrupt file (or a panic inside OIIO's FFI bindings) take down the whole batch.
+use crate::{caps, Error, ImageInput};
+use rayon::prelude::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+/// Outcome of processing a single image in a [read_all] batch.
+#[derive(Debug)]
+pub enum BatchResult<R> {
+    /// `f` ran to completion and returned `R`.
+    Ok(R),
+    /// The path's extension has no registered reader/writer (see [caps::can_read]); `f` was
+    /// never called for this entry.
+    Unsupported,
+    /// Opening or processing the image failed with `Error`.
+    Error(Error),
+    /// `f` (or the open/read it performed) panicked; the message is the panic payload,
+    /// downcast to a `String` where possible.
+    Panicked(String),
+}
+
+/// Opens every path in `paths` and applies `f` to the resulting [ImageInput], in parallel,
+/// isolating panics so that one bad image doesn't abort the whole batch.
+///
+/// Returns one `(path, BatchResult<R>)` per input path, in unspecified order relative to the
+/// input (use the returned path to re-associate results with inputs).
+pub fn read_all<P, F, R>(paths: &[P], f: F) -> Vec<(PathBuf, BatchResult<R>)>
+where
+    P: AsRef<Path> + Sync,
+    F: Fn(&mut ImageInput) -> Result<R, Error> + Sync,
+    R: Send,
+{
+    paths
+        .par_iter()
+        .map(|path| {
+            let path = path.as_ref().to_path_buf();
+
+            if !caps::can_read(&path) {
+                return (path, BatchResult::Unsupported);
+            }
+
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut input = ImageInput::open(&path)?;
+                f(&mut input)
+            }));
+
+            let result = match result {
+                Ok(Ok(value)) => BatchResult::Ok(value),
+                Ok(Err(error)) => BatchResult::Error(error),
+                Err(payload) => BatchResult::Panicked(panic_message(payload)),
+            };
+
+            (path, result)
+        })
+        .collect()
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        (*msg).to_owned()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "panic payload was not a string".to_owned()
+    }
+}
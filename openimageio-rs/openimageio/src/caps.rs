@@ -0,0 +1,76 @@
+//! Format capability discovery.
+//!
+//! Exposes which file formats (and extensions) the linked OIIO build actually has plugins for,
+//! so callers can tell "this extension has no reader/writer" apart from "the file is corrupt".
+use crate::Error;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::Path;
+
+/// Returns the map of format name (e.g. `"openexr"`) to the list of extensions it handles
+/// (e.g. `["exr"]`), as reported by OIIO's `"extension_list"` string attribute.
+pub fn supported_extensions() -> HashMap<String, Vec<String>> {
+    let raw = unsafe {
+        let cstr = openimageio_sys::OIIO_get_string_attribute(b"extension_list\0".as_ptr() as *const _);
+        CStr::from_ptr(cstr).to_str().unwrap().to_owned()
+    };
+
+    let mut map = HashMap::new();
+    for format_entry in raw.split(';') {
+        let mut it = format_entry.splitn(2, ':');
+        let format = match it.next() {
+            Some(f) if !f.is_empty() => f,
+            _ => continue,
+        };
+        let extensions = it
+            .next()
+            .map(|exts| exts.split(',').map(str::to_owned).collect())
+            .unwrap_or_default();
+        map.insert(format.to_owned(), extensions);
+    }
+    map
+}
+
+fn extension_of(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+}
+
+/// Returns `true` if some plugin in the linked OIIO build claims to be able to read files with
+/// the given path's extension.
+pub fn can_read(path: impl AsRef<Path>) -> bool {
+    is_extension_supported(path)
+}
+
+/// Returns `true` if some plugin in the linked OIIO build claims to be able to write files with
+/// the given path's extension.
+///
+/// OIIO reports readers and writers under the same `"extension_list"` attribute, so this is
+/// currently equivalent to [can_read].
+pub fn can_write(path: impl AsRef<Path>) -> bool {
+    is_extension_supported(path)
+}
+
+fn is_extension_supported(path: impl AsRef<Path>) -> bool {
+    match extension_of(path.as_ref()) {
+        Some(ext) => supported_extensions()
+            .values()
+            .any(|exts| exts.iter().any(|e| e.eq_ignore_ascii_case(&ext))),
+        None => false,
+    }
+}
+
+/// Returns `Err(Error::UnsupportedFormat)` if no plugin claims `path`'s extension.
+///
+/// Not called by [crate::ImageInput::open], which opens the file without this check;
+/// [crate::read_all] checks the extension with [can_read] instead.
+pub(crate) fn check_extension_supported(path: impl AsRef<Path>) -> Result<(), Error> {
+    let path = path.as_ref();
+    if !is_extension_supported(path) {
+        return Err(Error::UnsupportedFormat {
+            extension: extension_of(path).unwrap_or_default(),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Deep image (per-pixel variable sample count) read and write support.
+//!
+//! EXR and a handful of other OIIO formats carry "deep" data, where each pixel holds a variable
+//! number of samples (e.g. one per depth layer in a compositing stack) instead of one fixed-size
+//! value. This is backed by OIIO's `DeepData`.
+use crate::{Error, ImageInput, ImageOutput, ImageSpec};
+use openimageio_sys::OIIO_DeepData;
+
+impl ImageSpec {
+    /// Returns `true` if this subimage stores deep (variable-sample-count) data.
+    pub fn deep(&self) -> bool {
+        unsafe { openimageio_sys::OIIO_ImageSpec_deep(self.as_raw()) != 0 }
+    }
+}
+
+/// A deep image: for each pixel, a variable number of per-channel samples.
+///
+/// Obtained via [ImageInput::read_deep] and consumed via [ImageOutput::write_deep].
+pub struct DeepImageBuffer {
+    width: u32,
+    height: u32,
+    channels: u32,
+    inner: OIIO_DeepData,
+}
+
+impl DeepImageBuffer {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn channels(&self) -> u32 {
+        self.channels
+    }
+
+    /// Number of samples stored at pixel `(x, y)`.
+    pub fn sample_count(&self, x: u32, y: u32) -> usize {
+        unsafe { openimageio_sys::OIIO_DeepData_samples(&self.inner, (y * self.width + x) as i32) as usize }
+    }
+
+    /// Returns the per-sample values of channel `channel` at pixel `(x, y)`.
+    pub fn samples(&self, x: u32, y: u32, channel: u32) -> Vec<f32> {
+        let pixel = y * self.width + x;
+        let count = self.sample_count(x, y);
+        (0..count)
+            .map(|sample| unsafe {
+                openimageio_sys::OIIO_DeepData_deep_value(&self.inner, pixel as i32, channel as i32, sample as i32)
+            })
+            .collect()
+    }
+
+    /// Sets the per-sample values of channel `channel` at pixel `(x, y)`, resizing the pixel's
+    /// sample count first if it differs from `values.len()`.
+    pub fn set_samples(&mut self, x: u32, y: u32, channel: u32, values: &[f32]) {
+        let pixel = y * self.width + x;
+        unsafe {
+            openimageio_sys::OIIO_DeepData_set_samples(&mut self.inner, pixel as i32, values.len() as i32);
+            for (sample, &value) in values.iter().enumerate() {
+                openimageio_sys::OIIO_DeepData_set_deep_value(
+                    &mut self.inner,
+                    pixel as i32,
+                    channel as i32,
+                    sample as i32,
+                    value,
+                );
+            }
+        }
+    }
+}
+
+impl ImageInput {
+    /// Reads the current subimage as deep data.
+    ///
+    /// Returns [Error::ReadError] if the subimage is not deep (see [ImageSpec::deep]).
+    pub fn read_deep(&mut self) -> Result<DeepImageBuffer, Error> {
+        let spec = self.spec();
+        if !spec.deep() {
+            return Err(Error::ReadError("subimage does not contain deep data".to_owned()));
+        }
+        let width = self.width();
+        let height = self.height();
+        let channels = spec.channels().len() as u32;
+
+        unsafe {
+            let mut deep_data = OIIO_DeepData::new();
+            if openimageio_sys::OIIO_ImageInput_read_native_deep_image(self.as_raw_mut(), &mut deep_data) == 0 {
+                return Err(Error::ReadError(crate::error::get_last_error()));
+            }
+            Ok(DeepImageBuffer {
+                width,
+                height,
+                channels,
+                inner: deep_data,
+            })
+        }
+    }
+}
+
+impl ImageOutput {
+    /// Writes a deep image to the currently open subimage.
+    pub fn write_deep(&mut self, deep: &DeepImageBuffer) -> Result<(), Error> {
+        unsafe {
+            if openimageio_sys::OIIO_ImageOutput_write_deep_image(self.as_raw_mut(), &deep.inner) == 0 {
+                return Err(Error::WriteError(crate::error::get_last_error()));
+            }
+        }
+        Ok(())
+    }
+}
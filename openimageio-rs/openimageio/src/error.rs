@@ -12,6 +12,9 @@ pub enum Error {
     InvalidChannelIndex,
     InvalidAttributeNameOrType,
     BufferTooSmall,
+    /// No plugin claims the file's extension, as opposed to a plugin failing to parse its
+    /// contents (which is reported as [Error::OpenError]/[Error::ReadError]).
+    UnsupportedFormat { extension: String },
 }
 
 impl error::Error for Error {}
@@ -29,6 +32,9 @@ impl fmt::Display for Error {
             }
             Error::InvalidChannelIndex => write!(f, "non-existent channel index"),
             Error::BufferTooSmall => write!(f, "buffer was too small"),
+            Error::UnsupportedFormat { ref extension } => {
+                write!(f, "unsupported file format: no plugin registered for extension \"{}\"", extension)
+            }
             //_ => write!(f, "Unknown error."),
         }
     }
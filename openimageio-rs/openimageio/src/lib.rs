@@ -2,18 +2,28 @@ use std::ffi::CStr;
 use std::os::raw::c_char;
 
 mod attribute;
+mod batch;
 mod buffer;
 mod cache;
+mod caps;
+mod deep;
 mod error;
 mod input;
+mod mem_io;
 mod output;
 mod roi;
 mod spec;
+mod streaming;
+mod texture;
 mod typedesc;
 
+pub use batch::{read_all, BatchResult};
 pub use buffer::ImageBuffer;
+pub use caps::{can_read, can_write, supported_extensions};
+pub use deep::DeepImageBuffer;
 pub use error::Error;
 pub use input::ImageInput;
+pub use mem_io::MemoryImageOutput;
 pub use output::ImageOutput;
 pub use output::MultiImageOutput;
 pub use output::SingleImageOutput;
@@ -25,6 +35,9 @@ pub use spec::ChannelRGB;
 pub use spec::ChannelRGBA;
 pub use spec::ImageSpec;
 pub use spec::ImageSpecOwned;
+pub use streaming::{ProgressCallback, Scanlines, StreamElement, Tiles};
+pub use texture::Filter;
+pub use texture::TextureBuilder;
 pub use typedesc::Aggregate;
 pub use typedesc::BaseType;
 pub use typedesc::TypeDesc;
@@ -183,6 +196,63 @@ mod tests {
         assert!(img.is_err());
     }
 
+    #[test]
+    fn format_capability_discovery() {
+        let exts = supported_extensions();
+        assert!(exts.values().any(|e| e.iter().any(|e| e == "jpg" || e == "jpeg")));
+        assert!(can_read("../test_images/tonberry.jpg"));
+        assert!(!can_read("../test_images/tonberry.not_a_real_format"));
+    }
+
+    #[test]
+    fn open_image_from_memory() {
+        let bytes = std::fs::read("../test_images/tonberry.jpg").unwrap();
+        let img = ImageInput::open_memory(&bytes);
+        assert!(img.is_ok());
+    }
+
+    #[test]
+    fn scanline_streaming_with_progress() {
+        let mut img = ImageInput::open("../test_images/kazeharu.png").unwrap();
+        let height = img.height();
+        let mut done = 0;
+        let rows: Vec<_> = img
+            .scanlines::<f32>()
+            .with_progress(|y, total| {
+                assert_eq!(total, height);
+                done = y;
+                false
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(rows.len(), height as usize);
+        assert_eq!(done, height);
+    }
+
+    #[test]
+    fn batch_read_all() {
+        let paths = [
+            "../test_images/tonberry.jpg",
+            "../test_images/kazeharu.png",
+            "../test_images/nonexistent.png",
+            "../test_images/tonberry.not_a_real_format",
+        ];
+        let results = read_all(&paths, |img| Ok((img.width(), img.height())));
+        assert_eq!(results.len(), paths.len());
+        for (path, result) in &results {
+            match path.to_str().unwrap() {
+                "../test_images/tonberry.jpg" | "../test_images/kazeharu.png" => {
+                    assert!(matches!(result, BatchResult::Ok(_)))
+                }
+                "../test_images/nonexistent.png" => assert!(matches!(result, BatchResult::Error(_))),
+                "../test_images/tonberry.not_a_real_format" => {
+                    assert!(matches!(result, BatchResult::Unsupported))
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
     #[test]
     fn test_cache_api() {
         let cache = ImageCache::new();
@@ -0,0 +1,62 @@
+//! In-memory I/O via OIIO's `IOProxy` mechanism.
+//!
+//! Lets a reader/writer pull bytes from (or push bytes into) an in-memory buffer instead of
+//! going through the filesystem, which is what `Filesystem::IOMemReader`/`IOVecOutput` are for
+//! on the C++ side.
+use crate::{Error, ImageInput, ImageOutput, ImageSpec};
+use openimageio_sys::{OIIO_IOVecOutput, OIIO_IOMemReader};
+
+impl ImageInput {
+    /// Opens an image from an in-memory byte buffer.
+    ///
+    /// The buffer must remain valid for the lifetime of the returned `ImageInput`: internally,
+    /// an `IOMemReader` is created to wrap it and handed to the underlying `ImageInput::open`
+    /// overload that takes an `IOProxy`.
+    pub fn open_memory(data: &[u8]) -> Result<ImageInput, Error> {
+        unsafe {
+            let proxy = OIIO_IOMemReader::new(data.as_ptr(), data.len());
+            ImageInput::open_with_io_proxy(proxy)
+        }
+    }
+}
+
+impl ImageOutput {
+    /// Creates an image writer that appends its output to a growable in-memory buffer instead
+    /// of a file.
+    ///
+    /// The written bytes are retrieved with [MemoryImageOutput::into_vec] once the image has
+    /// been fully written.
+    pub fn create_memory(format_name: &str) -> Result<MemoryImageOutput, Error> {
+        unsafe {
+            let proxy = OIIO_IOVecOutput::new();
+            let out = ImageOutput::create_with_io_proxy(format_name, &proxy)?;
+            Ok(MemoryImageOutput { out, proxy })
+        }
+    }
+}
+
+/// An [ImageOutput] that writes into an in-memory buffer.
+///
+/// Obtained via [ImageOutput::create_memory]. Once `open` + `write_image` (or `write_scanline`,
+/// etc.) have completed, call [MemoryImageOutput::into_vec] to reclaim the encoded bytes.
+pub struct MemoryImageOutput {
+    out: ImageOutput,
+    proxy: OIIO_IOVecOutput,
+}
+
+impl MemoryImageOutput {
+    /// Opens a subimage on the wrapped [ImageOutput], same as [ImageOutput::open].
+    pub fn open(&mut self, spec: &ImageSpec) -> Result<&mut ImageOutput, Error> {
+        self.out.open(spec)?;
+        Ok(&mut self.out)
+    }
+
+    /// Finishes writing and returns the encoded image bytes.
+    ///
+    /// Must be called after all subimages have been written; the underlying `ImageOutput`
+    /// is closed as part of this call.
+    pub fn into_vec(mut self) -> Result<Vec<u8>, Error> {
+        self.out.close()?;
+        Ok(unsafe { self.proxy.into_vec() })
+    }
+}
@@ -0,0 +1,204 @@
+//! Incremental scanline/tile streaming reads.
+//!
+//! Unlike `read::<T>()`, which materializes the whole image into one [crate::ImageBuffer], these
+//! iterators decode and yield one scanline (or tile) at a time through a fixed-size working
+//! buffer. Every channel of the image is decoded; no channel subset is applied.
+use crate::{Error, ImageInput, TypeDesc};
+use std::marker::PhantomData;
+
+/// Invoked after each scanline/tile has been decoded, with `(done, total)` counts.
+///
+/// Returning `true` aborts the read early (the iterator then yields no further items).
+pub type ProgressCallback<'a> = dyn FnMut(u32, u32) -> bool + 'a;
+
+/// An element type that [Scanlines] and [Tiles] can decode pixel data into.
+///
+/// Implemented for the same scalar types `ImageInput::read::<T>()` supports.
+pub trait StreamElement: Copy + Default {
+    const TYPE_DESC: TypeDesc;
+}
+
+impl StreamElement for f32 {
+    const TYPE_DESC: TypeDesc = TypeDesc::FLOAT;
+}
+
+impl StreamElement for u8 {
+    const TYPE_DESC: TypeDesc = TypeDesc::UINT8;
+}
+
+impl StreamElement for u16 {
+    const TYPE_DESC: TypeDesc = TypeDesc::UINT16;
+}
+
+/// Iterator over the scanlines of an image, decoded one at a time.
+pub struct Scanlines<'a, T> {
+    input: &'a mut ImageInput,
+    y: u32,
+    height: u32,
+    width: u32,
+    channels: u32,
+    progress: Option<Box<ProgressCallback<'a>>>,
+    aborted: bool,
+    _marker: PhantomData<T>,
+}
+
+/// Iterator over the tiles of a tiled image, decoded one at a time.
+pub struct Tiles<'a, T> {
+    input: &'a mut ImageInput,
+    tile_x: u32,
+    tile_y: u32,
+    tile_width: u32,
+    tile_height: u32,
+    width: u32,
+    height: u32,
+    channels: u32,
+    progress: Option<Box<ProgressCallback<'a>>>,
+    aborted: bool,
+    _marker: PhantomData<T>,
+}
+
+impl ImageInput {
+    /// Returns an iterator that decodes and yields the image's scanlines one at a time.
+    pub fn scanlines<T: StreamElement>(&mut self) -> Scanlines<'_, T> {
+        let width = self.width();
+        let height = self.height();
+        let channels = self.spec().channels().len() as u32;
+        Scanlines {
+            input: self,
+            y: 0,
+            height,
+            width,
+            channels,
+            progress: None,
+            aborted: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that decodes and yields the image's tiles one at a time.
+    ///
+    /// Returns [Error::ReadError] if the image is not tiled.
+    pub fn tiles<T: StreamElement>(&mut self) -> Result<Tiles<'_, T>, Error> {
+        let spec = self.spec();
+        let (tile_width, tile_height) = spec.tile_size();
+        if tile_width == 0 || tile_height == 0 {
+            return Err(Error::ReadError("image is not tiled".to_owned()));
+        }
+        let width = self.width();
+        let height = self.height();
+        let channels = spec.channels().len() as u32;
+        Ok(Tiles {
+            input: self,
+            tile_x: 0,
+            tile_y: 0,
+            tile_width,
+            tile_height,
+            width,
+            height,
+            channels,
+            progress: None,
+            aborted: false,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: StreamElement> Scanlines<'a, T> {
+    /// Registers a callback invoked after each scanline with `(y_done, y_total)`. Returning
+    /// `true` from the callback cancels the remaining read.
+    pub fn with_progress(mut self, callback: impl FnMut(u32, u32) -> bool + 'a) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+}
+
+impl<'a, T: StreamElement> Iterator for Scanlines<'a, T> {
+    /// One decoded scanline, `width * channels` elements of `T`.
+    type Item = Result<Vec<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.aborted || self.y >= self.height {
+            return None;
+        }
+
+        let mut row = vec![T::default(); (self.width * self.channels) as usize];
+        let ok = unsafe {
+            openimageio_sys::OIIO_ImageInput_read_scanline(
+                self.input.as_raw_mut(),
+                self.y as i32,
+                0,
+                T::TYPE_DESC,
+                row.as_mut_ptr() as *mut _,
+            )
+        };
+        if ok == 0 {
+            return Some(Err(Error::ReadError(crate::error::get_last_error())));
+        }
+
+        self.y += 1;
+        if let Some(ref mut progress) = self.progress {
+            if progress(self.y, self.height) {
+                self.aborted = true;
+            }
+        }
+
+        Some(Ok(row))
+    }
+}
+
+impl<'a, T: StreamElement> Tiles<'a, T> {
+    /// Registers a callback invoked after each tile with `(tiles_done, tiles_total)`. Returning
+    /// `true` from the callback cancels the remaining read.
+    pub fn with_progress(mut self, callback: impl FnMut(u32, u32) -> bool + 'a) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    fn total_tiles(&self) -> u32 {
+        let tiles_x = (self.width + self.tile_width - 1) / self.tile_width;
+        let tiles_y = (self.height + self.tile_height - 1) / self.tile_height;
+        tiles_x * tiles_y
+    }
+}
+
+impl<'a, T: StreamElement> Iterator for Tiles<'a, T> {
+    /// One decoded tile, `tile_width * tile_height * channels` elements of `T`.
+    type Item = Result<Vec<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.aborted || self.tile_y >= self.height {
+            return None;
+        }
+
+        let mut tile = vec![T::default(); (self.tile_width * self.tile_height * self.channels) as usize];
+        let ok = unsafe {
+            openimageio_sys::OIIO_ImageInput_read_tile(
+                self.input.as_raw_mut(),
+                self.tile_x as i32,
+                self.tile_y as i32,
+                0,
+                T::TYPE_DESC,
+                tile.as_mut_ptr() as *mut _,
+            )
+        };
+        if ok == 0 {
+            return Some(Err(Error::ReadError(crate::error::get_last_error())));
+        }
+
+        self.tile_x += self.tile_width;
+        if self.tile_x >= self.width {
+            self.tile_x = 0;
+            self.tile_y += self.tile_height;
+        }
+
+        if let Some(ref mut progress) = self.progress {
+            let done = (self.tile_y / self.tile_height) * ((self.width + self.tile_width - 1) / self.tile_width)
+                + self.tile_x / self.tile_width;
+            if progress(done, self.total_tiles()) {
+                self.aborted = true;
+            }
+        }
+
+        Some(Ok(tile))
+    }
+}
@@ -0,0 +1,183 @@
+//! `maketx`-style tiled, mipmapped texture generation.
+//!
+//! Turns a flat [ImageBuffer] into a tiled, multi-level MIP-mapped file suitable for GPU texture
+//! caches, the way texture-authoring tools (e.g. `maketx`, nvidia-texture-tools) do.
+use crate::{Error, ImageOutput, ImageSpecOwned, TypeDesc};
+
+/// Downsampling filter used to generate each MIP level from the one above it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Filter {
+    /// Simple 2x2 box average.
+    Box,
+    /// 4x4 tent filter, weights `1/3/3/1` per axis, normalized.
+    Triangle,
+    /// Separable 5-tap Gaussian, weights `0.061/0.245/0.388/0.245/0.061` per axis, normalized.
+    Gaussian,
+}
+
+/// Builder for a tiled, mipmapped texture file.
+pub struct TextureBuilder {
+    tile_width: u32,
+    tile_height: u32,
+    filter: Filter,
+    wrap_mode: String,
+    bake_constant_color: bool,
+}
+
+impl Default for TextureBuilder {
+    fn default() -> Self {
+        TextureBuilder {
+            tile_width: 64,
+            tile_height: 64,
+            filter: Filter::Box,
+            wrap_mode: "black".to_owned(),
+            bake_constant_color: false,
+        }
+    }
+}
+
+impl TextureBuilder {
+    pub fn new() -> TextureBuilder {
+        Self::default()
+    }
+
+    /// Sets the tile size used for the output file. Defaults to 64x64.
+    pub fn tile_size(mut self, width: u32, height: u32) -> Self {
+        self.tile_width = width;
+        self.tile_height = height;
+        self
+    }
+
+    /// Sets the filter used to generate each MIP level from the previous one.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets the `"wrapmodes"` metadata baked into the output (e.g. `"black"`, `"clamp"`,
+    /// `"periodic"`, `"mirror"`).
+    pub fn wrap_mode(mut self, mode: impl Into<String>) -> Self {
+        self.wrap_mode = mode.into();
+        self
+    }
+
+    /// If set, the smallest (1x1) MIP level is replaced with the average color of the base
+    /// level, baked in as a constant, instead of being filtered down normally.
+    pub fn bake_constant_color_mip(mut self, bake: bool) -> Self {
+        self.bake_constant_color = bake;
+        self
+    }
+
+    /// Generates the MIP pyramid for `base` and writes it (base level first, then each
+    /// subsequent level appended to the same subimage) to `path`.
+    pub fn build(&self, base: &[f32], width: u32, height: u32, channels: u32, path: &str) -> Result<(), Error> {
+        let mut out = ImageOutput::create(path)?;
+
+        let mut spec = ImageSpecOwned::new_2d(TypeDesc::FLOAT, width as i32, height as i32, &channel_names(channels));
+        spec.set_tile_size(self.tile_width, self.tile_height);
+        spec.set_string_attribute("wrapmodes", &self.wrap_mode);
+
+        let mut level = out.open(&spec)?;
+        level.write_image(base)?;
+
+        let mut prev = base.to_vec();
+        let mut w = width;
+        let mut h = height;
+
+        while w > 1 || h > 1 {
+            let nw = (w / 2).max(1);
+            let nh = (h / 2).max(1);
+            let next = if self.bake_constant_color && nw == 1 && nh == 1 {
+                average_color(base, width, height, channels)
+            } else {
+                downsample(&prev, w, h, channels, nw, nh, self.filter)
+            };
+
+            let mip_spec = ImageSpecOwned::new_2d(TypeDesc::FLOAT, nw as i32, nh as i32, &channel_names(channels));
+            let mut level = out.open_mip_level(&mip_spec)?;
+            level.write_image(&next)?;
+
+            prev = next;
+            w = nw;
+            h = nh;
+        }
+
+        out.close()
+    }
+}
+
+fn channel_names(channels: u32) -> Vec<&'static str> {
+    match channels {
+        1 => vec!["Y"],
+        3 => vec!["R", "G", "B"],
+        4 => vec!["R", "G", "B", "A"],
+        _ => (0..channels).map(|_| "C").collect(),
+    }
+}
+
+fn average_color(data: &[f32], w: u32, h: u32, channels: u32) -> Vec<f32> {
+    let mut sum = vec![0.0f32; channels as usize];
+    let count = (w * h) as f32;
+    for texel in data.chunks(channels as usize) {
+        for (c, v) in texel.iter().enumerate() {
+            sum[c] += v;
+        }
+    }
+    sum.iter().map(|s| s / count).collect()
+}
+
+/// Downsamples `src` (dimensions `sw x sh`) into a `dw x dh` image using the given filter.
+fn downsample(src: &[f32], sw: u32, sh: u32, channels: u32, dw: u32, dh: u32, filter: Filter) -> Vec<f32> {
+    let mut dst = vec![0.0f32; (dw * dh * channels) as usize];
+
+    let sample = |x: i32, y: i32, c: u32| -> f32 {
+        let x = x.clamp(0, sw as i32 - 1) as u32;
+        let y = y.clamp(0, sh as i32 - 1) as u32;
+        src[((y * sw + x) * channels + c) as usize]
+    };
+
+    for dy in 0..dh {
+        for dx in 0..dw {
+            let sx = (dx * sw / dw) as i32;
+            let sy = (dy * sh / dh) as i32;
+            for c in 0..channels {
+                let value = match filter {
+                    Filter::Box => {
+                        (sample(sx, sy, c) + sample(sx + 1, sy, c) + sample(sx, sy + 1, c) + sample(sx + 1, sy + 1, c))
+                            / 4.0
+                    }
+                    Filter::Triangle => {
+                        // Separable 4-tap tent, weights 1/3/3/1 normalized over each axis.
+                        const W: [f32; 4] = [1.0, 3.0, 3.0, 1.0];
+                        let mut acc = 0.0;
+                        let mut wsum = 0.0;
+                        for (j, &wy) in W.iter().enumerate() {
+                            for (i, &wx) in W.iter().enumerate() {
+                                let weight = wx * wy;
+                                acc += weight * sample(sx - 1 + i as i32, sy - 1 + j as i32, c);
+                                wsum += weight;
+                            }
+                        }
+                        acc / wsum
+                    }
+                    Filter::Gaussian => {
+                        const W: [f32; 5] = [0.06136, 0.24477, 0.38774, 0.24477, 0.06136];
+                        let mut acc = 0.0;
+                        let mut wsum = 0.0;
+                        for (j, &wy) in W.iter().enumerate() {
+                            for (i, &wx) in W.iter().enumerate() {
+                                let weight = wx * wy;
+                                acc += weight * sample(sx - 2 + i as i32, sy - 2 + j as i32, c);
+                                wsum += weight;
+                            }
+                        }
+                        acc / wsum
+                    }
+                };
+                dst[((dy * dw + dx) * channels + c) as usize] = value;
+            }
+        }
+    }
+
+    dst
+}
@@ -0,0 +1,220 @@
+//! A growable `GL_TEXTURE_2D_ARRAY` atlas for batching many small images (sprites, glyphs, tiles)
+//! into a handful of bind slots, using shelf packing within each layer.
+use crate::api::Gl;
+use crate::image::{upload_image_region, RawImage};
+use autograph_render::{Dimensions, Format, ImageUsageFlags, MipmapsCount};
+
+/// One packed sub-image's location within an [ImageAtlas], in both texel and normalized UV form.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AtlasRect {
+    pub layer: u32,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub u0: f32,
+    pub v0: f32,
+    pub u1: f32,
+    pub v1: f32,
+}
+
+/// One shelf (horizontal strip) within an atlas layer: images are packed left-to-right along the
+/// shelf, and a new shelf opens below the tallest image so far once a shelf runs out of width.
+struct Shelf {
+    y: u32,
+    height: u32,
+    next_x: u32,
+}
+
+/// The shelf-packing state for a single array layer; holds no GL state of its own.
+struct Layer {
+    shelves: Vec<Shelf>,
+}
+
+impl Layer {
+    fn new() -> Layer {
+        Layer {
+            shelves: Vec::new(),
+        }
+    }
+
+    /// Finds or opens a shelf with room for `width`x`height`, returning its top-left corner.
+    fn pack(&mut self, atlas_size: u32, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width > atlas_size || height > atlas_size {
+            return None;
+        }
+
+        for shelf in self.shelves.iter_mut() {
+            if height <= shelf.height && atlas_size - shelf.next_x >= width {
+                let x = shelf.next_x;
+                shelf.next_x += width;
+                return Some((x, shelf.y));
+            }
+        }
+
+        let y = self
+            .shelves
+            .last()
+            .map_or(0, |shelf| shelf.y + shelf.height);
+        if y + height > atlas_size {
+            return None;
+        }
+        self.shelves.push(Shelf {
+            y,
+            height,
+            next_x: width,
+        });
+        Some((0, y))
+    }
+}
+
+/// A growable `GL_TEXTURE_2D_ARRAY` atlas: fixed `layer_size`x`layer_size` layers, each packed
+/// independently with shelf packing. Grows by reallocating the backing storage with one more
+/// layer (copying the existing layers across with `glCopyImageSubData`, since `TextureStorage3D`
+/// is immutable and can't be resized in place) once every layer is full.
+pub struct ImageAtlas {
+    raw: RawImage,
+    format: Format,
+    layer_size: u32,
+    layers: Vec<Layer>,
+}
+
+impl ImageAtlas {
+    /// Creates an atlas with a single `layer_size`x`layer_size` layer.
+    pub fn new(gl: &Gl, format: Format, layer_size: u32) -> ImageAtlas {
+        let raw = new_storage(gl, format, layer_size, 1);
+        ImageAtlas {
+            raw,
+            format,
+            layer_size,
+            layers: vec![Layer::new()],
+        }
+    }
+
+    /// Packs a `width`x`height` sub-image, uploads `data` into it (tightly packed, matching
+    /// [upload_image_region]'s layout), and returns its location. Grows the atlas by one layer if
+    /// no existing layer has room.
+    pub fn insert(&mut self, gl: &Gl, width: u32, height: u32, data: &[u8]) -> AtlasRect {
+        for (layer, layer_state) in self.layers.iter_mut().enumerate() {
+            if let Some((x, y)) = layer_state.pack(self.layer_size, width, height) {
+                return Self::upload(
+                    gl,
+                    &self.raw,
+                    self.format,
+                    self.layer_size,
+                    layer as u32,
+                    x,
+                    y,
+                    width,
+                    height,
+                    data,
+                );
+            }
+        }
+
+        self.grow(gl);
+        let layer = self.layers.len() - 1;
+        let (x, y) = self.layers[layer]
+            .pack(self.layer_size, width, height)
+            .expect(
+                "a freshly opened layer has room for any sub-image that fits within layer_size",
+            );
+        Self::upload(
+            gl,
+            &self.raw,
+            self.format,
+            self.layer_size,
+            layer as u32,
+            x,
+            y,
+            width,
+            height,
+            data,
+        )
+    }
+
+    fn upload(
+        gl: &Gl,
+        raw: &RawImage,
+        format: Format,
+        layer_size: u32,
+        layer: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        data: &[u8],
+    ) -> AtlasRect {
+        unsafe {
+            upload_image_region(
+                gl,
+                raw.target,
+                raw.obj,
+                format,
+                0,
+                (x, y, layer),
+                (width, height, 1),
+                data,
+                false,
+            );
+        }
+
+        AtlasRect {
+            layer,
+            x,
+            y,
+            width,
+            height,
+            u0: x as f32 / layer_size as f32,
+            v0: y as f32 / layer_size as f32,
+            u1: (x + width) as f32 / layer_size as f32,
+            v1: (y + height) as f32 / layer_size as f32,
+        }
+    }
+
+    /// Reallocates the backing `GL_TEXTURE_2D_ARRAY` with one more layer and copies every
+    /// previously packed layer across, then opens a new, empty packing layer on top of it.
+    fn grow(&mut self, gl: &Gl) {
+        let old = self.raw;
+        let old_layer_count = self.layers.len() as i32;
+
+        self.raw = new_storage(gl, self.format, self.layer_size, old_layer_count as u32 + 1);
+        unsafe {
+            gl.CopyImageSubData(
+                old.obj,
+                old.target,
+                0,
+                0,
+                0,
+                0,
+                self.raw.obj,
+                self.raw.target,
+                0,
+                0,
+                0,
+                0,
+                self.layer_size as i32,
+                self.layer_size as i32,
+                old_layer_count,
+            );
+        }
+        old.destroy(gl);
+
+        self.layers.push(Layer::new());
+    }
+}
+
+fn new_storage(gl: &Gl, format: Format, layer_size: u32, array_layers: u32) -> RawImage {
+    RawImage::new_texture(
+        gl,
+        format,
+        &Dimensions::Dim2dArray {
+            width: layer_size,
+            height: layer_size,
+            array_layers,
+        },
+        MipmapsCount::One,
+        1,
+        ImageUsageFlags::default(),
+    )
+}
@@ -0,0 +1,155 @@
+use crate::api as gl;
+use crate::api::types::*;
+use crate::api::Gl;
+use slotmap::new_key_type;
+
+/// Texel filtering mode, for minification/magnification and mipmap selection.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+/// How a sampler addresses texture coordinates outside of `[0, 1]`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum WrapMode {
+    ClampToEdge,
+    ClampToBorder,
+    Repeat,
+    MirroredRepeat,
+}
+
+/// Depth-compare function for a shadow sampler (`sampler2DShadow`-style sampling).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CompareFunc {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+/// Describes how an image is sampled, mirroring luminance's sampler description: filtering,
+/// addressing, LOD range, and an optional depth-compare mode.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SamplerDescription {
+    pub min_filter: Filter,
+    pub mag_filter: Filter,
+    pub mipmap_mode: Filter,
+    pub wrap_s: WrapMode,
+    pub wrap_t: WrapMode,
+    pub wrap_r: WrapMode,
+    pub lod_bias: f32,
+    pub min_lod: f32,
+    pub max_lod: f32,
+    /// `Some` makes the sampler a shadow sampler that compares fetched depth values against the
+    /// shader-supplied reference instead of returning them directly.
+    pub compare_op: Option<CompareFunc>,
+    /// Anisotropic filtering level; `1.0` disables it.
+    pub max_anisotropy: f32,
+}
+
+/// Wrapper for an OpenGL sampler object: filtering/addressing state bound at draw time via
+/// `glBindSampler`, independent of the image it samples.
+#[derive(Copy, Clone, Debug)]
+pub struct RawSampler {
+    pub obj: GLuint,
+}
+
+impl RawSampler {
+    pub fn new(gl: &Gl, desc: &SamplerDescription) -> RawSampler {
+        let mut obj = 0;
+        unsafe {
+            gl.CreateSamplers(1, &mut obj);
+
+            gl.SamplerParameteri(
+                obj,
+                gl::TEXTURE_MIN_FILTER,
+                min_filter_to_gl(desc.min_filter, desc.mipmap_mode) as i32,
+            );
+            gl.SamplerParameteri(
+                obj,
+                gl::TEXTURE_MAG_FILTER,
+                filter_to_gl(desc.mag_filter) as i32,
+            );
+            gl.SamplerParameteri(obj, gl::TEXTURE_WRAP_S, wrap_to_gl(desc.wrap_s) as i32);
+            gl.SamplerParameteri(obj, gl::TEXTURE_WRAP_T, wrap_to_gl(desc.wrap_t) as i32);
+            gl.SamplerParameteri(obj, gl::TEXTURE_WRAP_R, wrap_to_gl(desc.wrap_r) as i32);
+            gl.SamplerParameterf(obj, gl::TEXTURE_LOD_BIAS, desc.lod_bias);
+            gl.SamplerParameterf(obj, gl::TEXTURE_MIN_LOD, desc.min_lod);
+            gl.SamplerParameterf(obj, gl::TEXTURE_MAX_LOD, desc.max_lod);
+
+            if let Some(compare_op) = desc.compare_op {
+                gl.SamplerParameteri(
+                    obj,
+                    gl::TEXTURE_COMPARE_MODE,
+                    gl::COMPARE_REF_TO_TEXTURE as i32,
+                );
+                gl.SamplerParameteri(
+                    obj,
+                    gl::TEXTURE_COMPARE_FUNC,
+                    compare_func_to_gl(compare_op) as i32,
+                );
+            } else {
+                gl.SamplerParameteri(obj, gl::TEXTURE_COMPARE_MODE, gl::NONE as i32);
+            }
+
+            if desc.max_anisotropy > 1.0 {
+                gl.SamplerParameterf(obj, gl::TEXTURE_MAX_ANISOTROPY, desc.max_anisotropy);
+            }
+        }
+
+        RawSampler { obj }
+    }
+
+    pub fn destroy(&self, gl: &Gl) {
+        unsafe {
+            gl.DeleteSamplers(1, &self.obj);
+        }
+    }
+}
+
+fn filter_to_gl(filter: Filter) -> GLenum {
+    match filter {
+        Filter::Nearest => gl::NEAREST,
+        Filter::Linear => gl::LINEAR,
+    }
+}
+
+fn min_filter_to_gl(min_filter: Filter, mipmap_mode: Filter) -> GLenum {
+    match (min_filter, mipmap_mode) {
+        (Filter::Nearest, Filter::Nearest) => gl::NEAREST_MIPMAP_NEAREST,
+        (Filter::Nearest, Filter::Linear) => gl::NEAREST_MIPMAP_LINEAR,
+        (Filter::Linear, Filter::Nearest) => gl::LINEAR_MIPMAP_NEAREST,
+        (Filter::Linear, Filter::Linear) => gl::LINEAR_MIPMAP_LINEAR,
+    }
+}
+
+fn wrap_to_gl(wrap: WrapMode) -> GLenum {
+    match wrap {
+        WrapMode::ClampToEdge => gl::CLAMP_TO_EDGE,
+        WrapMode::ClampToBorder => gl::CLAMP_TO_BORDER,
+        WrapMode::Repeat => gl::REPEAT,
+        WrapMode::MirroredRepeat => gl::MIRRORED_REPEAT,
+    }
+}
+
+fn compare_func_to_gl(compare_op: CompareFunc) -> GLenum {
+    match compare_op {
+        CompareFunc::Never => gl::NEVER,
+        CompareFunc::Less => gl::LESS,
+        CompareFunc::Equal => gl::EQUAL,
+        CompareFunc::LessOrEqual => gl::LEQUAL,
+        CompareFunc::Greater => gl::GREATER,
+        CompareFunc::NotEqual => gl::NOTEQUAL,
+        CompareFunc::GreaterOrEqual => gl::GEQUAL,
+        CompareFunc::Always => gl::ALWAYS,
+    }
+}
+
+new_key_type! {
+    pub struct SamplerKey;
+}
@@ -0,0 +1,112 @@
+//! Ray-tracing acceleration structures and the commands to build them, following the builder
+//! pattern used by vulkan-rs's acceleration-structure support.
+use crate::{BufferSlice, IndexFormat, RendererBackend};
+use bitflags::bitflags;
+use derivative::Derivative;
+
+bitflags! {
+    /// Build-time hints and permissions, mirroring the subset of
+    /// `VkBuildAccelerationStructureFlagBitsKHR` this crate's backends need.
+    pub struct AccelerationStructureBuildFlags: u32 {
+        /// Favor trace performance over build time.
+        const PREFER_FAST_TRACE = 0b0000_0001;
+        /// Favor build time over trace performance.
+        const PREFER_FAST_BUILD = 0b0000_0010;
+        /// Allow this structure to later be refitted in place with [BuildMode::Update], instead of
+        /// only ever being fully rebuilt.
+        const ALLOW_UPDATE = 0b0000_0100;
+        /// Allow this structure to be compacted after building.
+        const ALLOW_COMPACTION = 0b0000_1000;
+        const LOW_MEMORY = 0b0001_0000;
+    }
+}
+
+bitflags! {
+    /// Per-instance flags in a [AccelerationStructureInstance], mirroring the subset of
+    /// `VkGeometryInstanceFlagBitsKHR` this crate's backends need.
+    pub struct GeometryInstanceFlags: u32 {
+        const TRIANGLE_FACING_CULL_DISABLE = 0b0000_0001;
+        const TRIANGLE_FLIP_FACING = 0b0000_0010;
+        const FORCE_OPAQUE = 0b0000_0100;
+        const FORCE_NO_OPAQUE = 0b0000_1000;
+    }
+}
+
+/// Whether a [CommandInner::BuildAccelerationStructure](crate::cmd::CommandInner::BuildAccelerationStructure)
+/// builds `dst` from scratch or refits it in place.
+///
+/// `Update` is only valid for a `dst` previously built with
+/// [AccelerationStructureBuildFlags::ALLOW_UPDATE] set.
+#[derive(Copy, Clone, Debug)]
+pub enum BuildMode {
+    Build(AccelerationStructureBuildFlags),
+    Update,
+}
+
+/// A bottom-level acceleration structure (BLAS): geometry (triangles or AABBs) in a single
+/// bottom-level structure, referenced by instances in a [TopLevelAccelerationStructure].
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
+pub struct BottomLevelAccelerationStructure<'a, R: RendererBackend> {
+    pub accel: &'a R::AccelerationStructure,
+}
+
+/// A top-level acceleration structure (TLAS): a set of instances of [BottomLevelAccelerationStructure]s,
+/// each with its own transform. Bound through the existing [PipelineInterface](crate::interface::PipelineInterface)
+/// descriptor-set path, the same way other shader-visible resources are.
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
+pub struct TopLevelAccelerationStructure<'a, R: RendererBackend> {
+    pub accel: &'a R::AccelerationStructure,
+}
+
+/// One piece of geometry fed into a BLAS build, or the instance buffer fed into a TLAS build.
+#[derive(Derivative)]
+#[derivative(Clone(bound = ""))]
+pub enum AccelerationStructureGeometry<'a, R: RendererBackend> {
+    /// Triangle geometry for a BLAS build.
+    Triangles {
+        vertex_buffer: BufferSlice<'a, R>,
+        vertex_stride: u64,
+        max_vertex: u32,
+        /// `None` for an unindexed triangle list.
+        index_buffer: Option<BufferSlice<'a, R>>,
+        index_format: IndexFormat,
+    },
+    /// Axis-aligned bounding-box geometry for a BLAS build (e.g. for custom-intersection
+    /// primitives).
+    Aabbs {
+        buffer: BufferSlice<'a, R>,
+        stride: u64,
+    },
+    /// The instance buffer for a TLAS build: `instance_count` tightly-packed
+    /// [AccelerationStructureInstance]-layout records.
+    Instances {
+        buffer: BufferSlice<'a, R>,
+        instance_count: u32,
+    },
+}
+
+/// One instance in a TLAS's instance buffer, referencing a BLAS with its own transform, mirroring
+/// `VkAccelerationStructureInstanceKHR`.
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
+pub struct AccelerationStructureInstance<'a, R: RendererBackend> {
+    pub blas: BottomLevelAccelerationStructure<'a, R>,
+    /// Row-major 3x4 object-to-world transform matrix.
+    pub transform: [[f32; 4]; 3],
+    pub instance_custom_index: u32,
+    pub mask: u8,
+    pub shader_binding_table_record_offset: u32,
+    pub flags: GeometryInstanceFlags,
+}
+
+/// The destination of a
+/// [CommandInner::BuildAccelerationStructure](crate::cmd::CommandInner::BuildAccelerationStructure),
+/// since BLAS and TLAS builds write to different kinds of structure.
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
+pub enum AccelerationStructureHandle<'a, R: RendererBackend> {
+    BottomLevel(BottomLevelAccelerationStructure<'a, R>),
+    TopLevel(TopLevelAccelerationStructure<'a, R>),
+}
@@ -1,9 +1,12 @@
 use crate::traits;
 use crate::typedesc::PrimitiveType;
 use crate::typedesc::TypeDesc;
+use derivative::Derivative;
 use std::marker::PhantomData;
 use std::mem;
 
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
 pub struct BufferSlice<'a, R: RendererBackend> {
     pub buffer: &'a R::Buffer,
     pub offset: usize,
@@ -1,3 +1,9 @@
+use crate::accel::{
+    AccelerationStructureGeometry, AccelerationStructureHandle,
+    BottomLevelAccelerationStructure, BuildMode, TopLevelAccelerationStructure,
+};
+use crate::image::{self, BufferImageCopy, Filter, ImageBlit, ImageCopy};
+use crate::query::{PipelineStatisticFlags, QueryPool};
 use crate::sync::*;
 use crate::{
     interface::{PipelineInterface, PipelineInterfaceVisitor},
@@ -5,7 +11,10 @@ use crate::{
     RendererBackend, ScissorRect, Swapchain, Viewport,
 };
 use derivative::Derivative;
+use std::collections::HashMap;
+use std::mem;
 use std::ops::Range;
+use std::rc::Rc;
 use crate::interface::VertexBufferDescriptor;
 use crate::interface::IndexBufferDescriptor;
 
@@ -61,7 +70,11 @@ pub struct DrawIndexedParams {
 #[derivative(Clone(bound = ""))]
 pub enum CommandInner<'a, R: RendererBackend> {
     // MAIN (LEAD-IN) COMMANDS ---------------------------------------------------------------------
-    PipelineBarrier {},
+    PipelineBarrier {
+        src_stage_mask: PipelineStageFlags,
+        dst_stage_mask: PipelineStageFlags,
+        memory_barriers: Vec<MemoryBarrier<R>>,
+    },
     ClearImageFloat {
         image: Image<'a, R>,
         color: [f32; 4],
@@ -79,6 +92,38 @@ pub enum CommandInner<'a, R: RendererBackend> {
         pipeline: GraphicsPipeline<'a, R>,
     },
 
+    // TRANSFER COMMANDS -----------------------------------------------------------------------
+    CopyBuffer {
+        src: BufferTypeless<'a, R>,
+        dst: BufferTypeless<'a, R>,
+        src_range: Range<u64>,
+        dst_range: Range<u64>,
+    },
+    CopyBufferToImage {
+        src_buffer: BufferTypeless<'a, R>,
+        dst_image: Image<'a, R>,
+        regions: Vec<BufferImageCopy>,
+    },
+    CopyImageToBuffer {
+        src_image: Image<'a, R>,
+        dst_buffer: BufferTypeless<'a, R>,
+        regions: Vec<BufferImageCopy>,
+    },
+    CopyImage {
+        src: Image<'a, R>,
+        dst: Image<'a, R>,
+        regions: Vec<ImageCopy>,
+    },
+    BlitImage {
+        src: Image<'a, R>,
+        dst: Image<'a, R>,
+        regions: Vec<ImageBlit>,
+        filter: Filter,
+    },
+    ExecuteCommands {
+        buffers: Vec<SecondaryCommandBuffer<'a, R>>,
+    },
+
     // STATE CHANGE COMMANDS -----------------------------------------------------------------------
     SetDescriptorSets {
         descriptor_sets: Vec<DescriptorSet<'a, R>>,
@@ -117,6 +162,45 @@ pub enum CommandInner<'a, R: RendererBackend> {
         vertex_offset: i32,
         first_instance: u32,
     },
+    DrawIndirect {
+        buffer: BufferTypeless<'a, R>,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+    DrawIndexedIndirect {
+        buffer: BufferTypeless<'a, R>,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    },
+
+    // QUERY COMMANDS ----------------------------------------------------------------------------
+    ResetQueryPool {
+        pool: QueryPool<'a, R>,
+    },
+    WriteTimestamp {
+        pool: QueryPool<'a, R>,
+        query: u32,
+        stage: PipelineStageFlags,
+    },
+    BeginQuery {
+        pool: QueryPool<'a, R>,
+        query: u32,
+        statistics_flags: PipelineStatisticFlags,
+    },
+    EndQuery {
+        pool: QueryPool<'a, R>,
+        query: u32,
+    },
+
+    // RAY TRACING COMMANDS ------------------------------------------------------------------------
+    BuildAccelerationStructure {
+        dst: AccelerationStructureHandle<'a, R>,
+        geometry: Vec<AccelerationStructureGeometry<'a, R>>,
+        scratch: BufferTypeless<'a, R>,
+        mode: BuildMode,
+    },
 }
 /*
 // Explicit clone impl because of #26925
@@ -213,6 +297,36 @@ pub struct CommandBuffer<'a, R: RendererBackend> {
     commands: Vec<Command<'a, R>>,
 }
 
+/// A command buffer recorded once and replayed by reference through
+/// [CommandBuffer::execute_commands], instead of being fused and sorted directly by
+/// [sort_command_buffers] every frame.
+///
+/// Wrapping the recorded commands in an `Rc` means executing the same secondary buffer from
+/// several primary buffers, or across several frames, is just a refcount bump when recording,
+/// and the recorded commands are never re-sorted. [sort_command_buffers] does clone them into the
+/// frame's command stream each time it splices in an `ExecuteCommands` entry.
+pub struct SecondaryCommandBuffer<'a, R: RendererBackend> {
+    commands: Rc<Vec<Command<'a, R>>>,
+}
+
+impl<'a, R: RendererBackend> SecondaryCommandBuffer<'a, R> {
+    /// Freezes `buffer`'s recorded commands into a reusable, reference-counted secondary command
+    /// buffer.
+    pub fn new(buffer: CommandBuffer<'a, R>) -> SecondaryCommandBuffer<'a, R> {
+        SecondaryCommandBuffer {
+            commands: Rc::new(buffer.commands),
+        }
+    }
+}
+
+impl<'a, R: RendererBackend> Clone for SecondaryCommandBuffer<'a, R> {
+    fn clone(&self) -> Self {
+        SecondaryCommandBuffer {
+            commands: self.commands.clone(),
+        }
+    }
+}
+
 /// API exposed by command buffers.
 /// Can build multiple command buffers concurrently in different threads.
 impl<'a, R: RendererBackend> CommandBuffer<'a, R> {
@@ -237,14 +351,27 @@ impl<'a, R: RendererBackend> CommandBuffer<'a, R> {
     // Manual sync
 
     /// Inserts an explicit pipeline barrier.
+    ///
+    /// [sort_command_buffers] already inserts barriers automatically for the resources commands
+    /// report through [CommandInner::resource_accesses] (resources behind descriptor sets and
+    /// framebuffers, query pools and acceleration-structure build buffers are not among them);
+    /// reach for this instead for a dependency its pass can't see, e.g. one of those, or a
+    /// resource also written to from outside any tracked command buffer.
     pub fn pipeline_barrier(
         &mut self,
-        _sort_key: u64,
-        _src: PipelineStageFlags,
-        _dst: PipelineStageFlags,
-        _memory_barriers: &[MemoryBarrier<R>],
+        sort_key: u64,
+        src_stage_mask: PipelineStageFlags,
+        dst_stage_mask: PipelineStageFlags,
+        memory_barriers: &[MemoryBarrier<R>],
     ) {
-        unimplemented!()
+        self.push_command(
+            sort_key,
+            CommandInner::PipelineBarrier {
+                src_stage_mask,
+                dst_stage_mask,
+                memory_barriers: memory_barriers.to_vec(),
+            },
+        )
     }
 
     //----------------------------------------------------------------------------------------------
@@ -256,13 +383,170 @@ impl<'a, R: RendererBackend> CommandBuffer<'a, R> {
     /// Copy data between buffers.
     pub fn copy_buffer(
         &mut self,
-        _sort_key: u64,
-        _src: BufferTypeless<'a, R>,
-        _dst: BufferTypeless<'a, R>,
-        _src_range: Range<u64>,
-        _dst_range: Range<u64>,
+        sort_key: u64,
+        src: BufferTypeless<'a, R>,
+        dst: BufferTypeless<'a, R>,
+        src_range: Range<u64>,
+        dst_range: Range<u64>,
     ) {
-        unimplemented!()
+        self.push_command(
+            sort_key,
+            CommandInner::CopyBuffer {
+                src,
+                dst,
+                src_range,
+                dst_range,
+            },
+        )
+    }
+
+    /// Copies `regions` of `src_buffer` into `dst_image`, e.g. to upload texture data.
+    pub fn copy_buffer_to_image(
+        &mut self,
+        sort_key: u64,
+        src_buffer: BufferTypeless<'a, R>,
+        dst_image: Image<'a, R>,
+        regions: &[BufferImageCopy],
+    ) {
+        #[cfg(debug_assertions)]
+        {
+            let errors: Vec<_> = regions
+                .iter()
+                .flat_map(image::validate_buffer_image_copy)
+                .collect();
+            if !errors.is_empty() {
+                for e in &errors {
+                    log::error!("validation error: {}", e);
+                }
+                panic!("buffer-to-image copy region validation failed");
+            }
+        }
+        self.push_command(
+            sort_key,
+            CommandInner::CopyBufferToImage {
+                src_buffer,
+                dst_image,
+                regions: regions.to_vec(),
+            },
+        )
+    }
+
+    /// Copies `regions` of `src_image` into `dst_buffer`, e.g. for a readback.
+    pub fn copy_image_to_buffer(
+        &mut self,
+        sort_key: u64,
+        src_image: Image<'a, R>,
+        dst_buffer: BufferTypeless<'a, R>,
+        regions: &[BufferImageCopy],
+    ) {
+        #[cfg(debug_assertions)]
+        {
+            let errors: Vec<_> = regions
+                .iter()
+                .flat_map(image::validate_buffer_image_copy)
+                .collect();
+            if !errors.is_empty() {
+                for e in &errors {
+                    log::error!("validation error: {}", e);
+                }
+                panic!("image-to-buffer copy region validation failed");
+            }
+        }
+        self.push_command(
+            sort_key,
+            CommandInner::CopyImageToBuffer {
+                src_image,
+                dst_buffer,
+                regions: regions.to_vec(),
+            },
+        )
+    }
+
+    /// Copies `regions` of `src` into `dst`, texel-for-texel; see
+    /// [blit_image](CommandBuffer::blit_image) for a version that can scale or filter.
+    pub fn copy_image(
+        &mut self,
+        sort_key: u64,
+        src: Image<'a, R>,
+        dst: Image<'a, R>,
+        regions: &[ImageCopy],
+    ) {
+        #[cfg(debug_assertions)]
+        {
+            let errors: Vec<_> = regions
+                .iter()
+                .flat_map(image::validate_image_copy)
+                .collect();
+            if !errors.is_empty() {
+                for e in &errors {
+                    log::error!("validation error: {}", e);
+                }
+                panic!("image copy region validation failed");
+            }
+        }
+        self.push_command(
+            sort_key,
+            CommandInner::CopyImage {
+                src,
+                dst,
+                regions: regions.to_vec(),
+            },
+        )
+    }
+
+    /// Copies `regions` of `src` into `dst`, scaling (and, per `filter`, resampling) a region
+    /// whose source and destination boxes differ in size.
+    pub fn blit_image(
+        &mut self,
+        sort_key: u64,
+        src: Image<'a, R>,
+        dst: Image<'a, R>,
+        regions: &[ImageBlit],
+        filter: Filter,
+    ) {
+        #[cfg(debug_assertions)]
+        {
+            let errors: Vec<_> = regions
+                .iter()
+                .flat_map(image::validate_image_blit)
+                .collect();
+            if !errors.is_empty() {
+                for e in &errors {
+                    log::error!("validation error: {}", e);
+                }
+                panic!("image blit region validation failed");
+            }
+        }
+        self.push_command(
+            sort_key,
+            CommandInner::BlitImage {
+                src,
+                dst,
+                regions: regions.to_vec(),
+                filter,
+            },
+        )
+    }
+
+    //----------------------------------------------------------------------------------------------
+    // Secondary command buffers
+
+    /// Executes `buffers`, in the order given, at `sortkey`.
+    ///
+    /// Each secondary buffer's recorded commands participate in the global stable sort as a
+    /// single atomic unit anchored at `sortkey`: [sort_command_buffers] keeps their recorded
+    /// relative order instead of re-sorting them against the rest of the stream.
+    pub fn execute_commands<I: IntoIterator<Item = SecondaryCommandBuffer<'a, R>>>(
+        &mut self,
+        sortkey: u64,
+        buffers: I,
+    ) {
+        self.push_command(
+            sortkey,
+            CommandInner::ExecuteCommands {
+                buffers: buffers.into_iter().collect(),
+            },
+        )
     }
 
     //----------------------------------------------------------------------------------------------
@@ -447,30 +731,718 @@ impl<'a, R: RendererBackend> CommandBuffer<'a, R> {
         );
     }
 
+    /// Issues a non-indexed draw whose parameters are read from `buffer` at draw time, instead of
+    /// being supplied from the CPU.
+    ///
+    /// `buffer` must hold `draw_count` tightly-packed (or `stride`-separated)
+    /// `{vertex_count, instance_count, first_vertex, first_instance}` records, matching vulkano's
+    /// `DrawIndirectCommand` layout.
+    pub fn draw_indirect<PI: PipelineInterface<'a, R>>(
+        &mut self,
+        sortkey: u64,
+        pipeline: GraphicsPipeline<'a, R>,
+        interface: &PI,
+        buffer: BufferTypeless<'a, R>,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        self.bind_pipeline_interface(sortkey, pipeline, interface);
+        self.push_command(
+            sortkey,
+            CommandInner::DrawIndirect {
+                buffer,
+                offset,
+                draw_count,
+                stride,
+            },
+        );
+    }
+
+    /// Issues an indexed draw whose parameters are read from `buffer` at draw time, instead of
+    /// being supplied from the CPU.
+    ///
+    /// `buffer` must hold `draw_count` tightly-packed (or `stride`-separated)
+    /// `{index_count, instance_count, first_index, vertex_offset, first_instance}` records,
+    /// matching vulkano's `DrawIndexedIndirectCommand` layout.
+    pub fn draw_indexed_indirect<PI: PipelineInterface<'a, R>>(
+        &mut self,
+        sortkey: u64,
+        pipeline: GraphicsPipeline<'a, R>,
+        interface: &PI,
+        buffer: BufferTypeless<'a, R>,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        self.bind_pipeline_interface(sortkey, pipeline, interface);
+        self.push_command(
+            sortkey,
+            CommandInner::DrawIndexedIndirect {
+                buffer,
+                offset,
+                draw_count,
+                stride,
+            },
+        );
+    }
+
+    //----------------------------------------------------------------------------------------------
+    // Queries
+
+    /// Resets every slot of `pool` to the unavailable state, ready to be written again.
+    ///
+    /// Must happen-before any [write_timestamp](CommandBuffer::write_timestamp) or
+    /// [begin_query](CommandBuffer::begin_query) targeting one of its slots in the same frame.
+    pub fn reset_query_pool(&mut self, sortkey: u64, pool: QueryPool<'a, R>) {
+        self.push_command(sortkey, CommandInner::ResetQueryPool { pool })
+    }
+
+    /// Writes a GPU timestamp into `pool`'s `query`-th slot once every command sorted before this
+    /// one has reached `stage`.
+    pub fn write_timestamp(
+        &mut self,
+        sortkey: u64,
+        pool: QueryPool<'a, R>,
+        query: u32,
+        stage: PipelineStageFlags,
+    ) {
+        self.push_command(sortkey, CommandInner::WriteTimestamp { pool, query, stage })
+    }
+
+    /// Starts gathering `statistics_flags` into `pool`'s `query`-th slot, until the matching
+    /// [end_query](CommandBuffer::end_query).
+    pub fn begin_query(
+        &mut self,
+        sortkey: u64,
+        pool: QueryPool<'a, R>,
+        query: u32,
+        statistics_flags: PipelineStatisticFlags,
+    ) {
+        self.push_command(
+            sortkey,
+            CommandInner::BeginQuery {
+                pool,
+                query,
+                statistics_flags,
+            },
+        )
+    }
+
+    /// Stops gathering statistics started by the matching
+    /// [begin_query](CommandBuffer::begin_query).
+    pub fn end_query(&mut self, sortkey: u64, pool: QueryPool<'a, R>, query: u32) {
+        self.push_command(sortkey, CommandInner::EndQuery { pool, query })
+    }
+
+    //----------------------------------------------------------------------------------------------
+    // Ray tracing
+
+    /// Builds `dst` from `geometry` (triangles and/or AABBs), using `scratch` as scratch space.
+    ///
+    /// `mode` is [BuildMode::Build] the first time `dst` is built, and can be
+    /// [BuildMode::Update] on a later frame if that first build set
+    /// [AccelerationStructureBuildFlags::ALLOW_UPDATE](crate::accel::AccelerationStructureBuildFlags::ALLOW_UPDATE).
+    pub fn build_blas(
+        &mut self,
+        sortkey: u64,
+        dst: BottomLevelAccelerationStructure<'a, R>,
+        geometry: &[AccelerationStructureGeometry<'a, R>],
+        scratch: BufferTypeless<'a, R>,
+        mode: BuildMode,
+    ) {
+        self.push_command(
+            sortkey,
+            CommandInner::BuildAccelerationStructure {
+                dst: AccelerationStructureHandle::BottomLevel(dst),
+                geometry: geometry.to_vec(),
+                scratch,
+                mode,
+            },
+        )
+    }
+
+    /// Builds `dst` from `instances` (an [AccelerationStructureGeometry::Instances] geometry),
+    /// using `scratch` as scratch space.
+    ///
+    /// Pass [BuildMode::Build] with [AccelerationStructureBuildFlags::ALLOW_UPDATE](crate::accel::AccelerationStructureBuildFlags::ALLOW_UPDATE) set to allow
+    /// `dst` to be cheaply refitted with [BuildMode::Update] on later frames, instead of fully
+    /// rebuilt, as instance transforms change.
+    pub fn build_tlas(
+        &mut self,
+        sortkey: u64,
+        dst: TopLevelAccelerationStructure<'a, R>,
+        instances: AccelerationStructureGeometry<'a, R>,
+        scratch: BufferTypeless<'a, R>,
+        mode: BuildMode,
+    ) {
+        self.push_command(
+            sortkey,
+            CommandInner::BuildAccelerationStructure {
+                dst: AccelerationStructureHandle::TopLevel(dst),
+                geometry: vec![instances],
+                scratch,
+                mode,
+            },
+        )
+    }
+
     //----------------------------------------------------------------------------------------------
     // Present
 
     /// Presents the specified image to the swapchain.
-    /// Might incur a copy / blit or format conversion if necessary.
+    ///
+    /// FIXME: this should reuse [blit_image](CommandBuffer::blit_image) when `image`'s format
+    /// doesn't match the swapchain's, but `Image`/`Swapchain` don't expose their format in this
+    /// crate yet, so `Present` always assumes they already match.
     pub fn present(&mut self, sortkey: u64, image: Image<'a, R>, swapchain: Swapchain<'a, R>) {
         self.push_command(sortkey, CommandInner::Present { image, swapchain })
     }
 }
 
-/// TODO optimize (radix sort, dense command buffer layout, separate index map)
 pub fn sort_command_buffers<'a, R: RendererBackend>(
     cmdbufs: Vec<CommandBuffer<'a, R>>,
 ) -> Vec<Command<'a, R>> {
     let mut fused = Vec::new();
-    //let mut sortkeys = Vec::new();
-    //let mut i: usize = 0;
-    for cmdbuf in cmdbufs.iter() {
-        for cmd in cmdbuf.commands.iter() {
-            fused.push(cmd.clone());
-            //sortkeys.push(cmd.sortkey);
+    for cmdbuf in cmdbufs {
+        fused.extend(cmdbuf.commands);
+    }
+
+    let order = radix_sort_indices(&fused);
+
+    // Materialize the final ordered list by moving each command out exactly once, instead of
+    // cloning into a second array and comparison-sorting it.
+    let mut slots: Vec<Option<Command<'a, R>>> = fused.into_iter().map(Some).collect();
+    let mut sorted = Vec::with_capacity(slots.len());
+    for i in order {
+        sorted.push(
+            slots[i]
+                .take()
+                .expect("radix_sort_indices yields each index exactly once"),
+        );
+    }
+
+    insert_pipeline_barriers(expand_execute_commands(sorted))
+}
+
+/// Returns the indices of `commands` in ascending `sortkey` order, using an 8-pass LSB-first radix
+/// sort over the 64-bit sortkey (8-bit buckets) instead of a comparison sort.
+///
+/// Each pass is a stable counting-sort scatter driven by a 256-entry histogram, ping-ponging
+/// `indices` between two scratch buffers reused across all 8 passes. Starting from indices in
+/// insertion order and sorting least-significant byte first keeps the sort stable throughout, so
+/// commands with equal sortkeys come out in their original insertion order — no separate tiebreak
+/// needed.
+fn radix_sort_indices<'a, R: RendererBackend>(commands: &[Command<'a, R>]) -> Vec<usize> {
+    let len = commands.len();
+    let mut src: Vec<usize> = (0..len).collect();
+    let mut dst: Vec<usize> = vec![0; len];
+    let mut histogram = [0usize; 256];
+
+    for pass in 0..8 {
+        let shift = pass * 8;
+
+        for bucket in histogram.iter_mut() {
+            *bucket = 0;
+        }
+        for &i in &src {
+            let bucket = ((commands[i].sortkey >> shift) & 0xff) as usize;
+            histogram[bucket] += 1;
+        }
+
+        // Turn per-bucket counts into starting offsets.
+        let mut offset = 0;
+        for count in histogram.iter_mut() {
+            let bucket_count = *count;
+            *count = offset;
+            offset += bucket_count;
+        }
+
+        for &i in &src {
+            let bucket = ((commands[i].sortkey >> shift) & 0xff) as usize;
+            dst[histogram[bucket]] = i;
+            histogram[bucket] += 1;
+        }
+
+        mem::swap(&mut src, &mut dst);
+    }
+
+    src
+}
+
+/// Replaces each [CommandInner::ExecuteCommands] entry in `sorted` (already sorted by sortkey)
+/// with its secondary buffers' recorded commands, concatenated in the order given to
+/// [CommandBuffer::execute_commands]. The nested commands keep their recorded relative order —
+/// they're spliced in as a block, not merged into the surrounding stream by their own sortkeys —
+/// so an `ExecuteCommands` entry behaves as a single atomic unit anchored at its own sortkey.
+fn expand_execute_commands<'a, R: RendererBackend>(
+    sorted: Vec<Command<'a, R>>,
+) -> Vec<Command<'a, R>> {
+    let mut out = Vec::with_capacity(sorted.len());
+    for Command { sortkey, cmd } in sorted {
+        match cmd {
+            CommandInner::ExecuteCommands { buffers } => {
+                for secondary in &buffers {
+                    out.extend(secondary.commands.iter().cloned());
+                }
+            }
+            cmd => out.push(Command { sortkey, cmd }),
+        }
+    }
+    out
+}
+
+//----------------------------------------------------------------------------------------------
+// Automatic synchronization
+
+/// One resource a [CommandInner] reads or writes, and how, for [insert_pipeline_barriers].
+struct ResourceAccess {
+    key: ResourceKey,
+    stage: PipelineStageFlags,
+    access: AccessFlags,
+    /// `Some` for images, which care about layout; `None` for buffers, which don't.
+    layout: Option<ImageLayout>,
+}
+
+impl<'a, R: RendererBackend> CommandInner<'a, R> {
+    /// Resources this command reads or writes, for [insert_pipeline_barriers].
+    ///
+    /// FIXME: `SetDescriptorSets` and `SetFramebuffer` don't report the individual resources they
+    /// bind — the buffers/images behind a descriptor set, or the attachments behind a framebuffer
+    /// — because those types don't expose their contents here yet. Until they do, a resource bound
+    /// only through a descriptor set or a framebuffer attachment isn't automatically synchronized;
+    /// callers relying on one should insert an explicit
+    /// [pipeline_barrier](CommandBuffer::pipeline_barrier) instead.
+    fn resource_accesses(&self) -> Vec<ResourceAccess> {
+        match self {
+            CommandInner::ClearImageFloat { image, .. } => vec![ResourceAccess {
+                key: image_key(image),
+                stage: PipelineStageFlags::TRANSFER,
+                access: AccessFlags::TRANSFER_WRITE,
+                layout: Some(ImageLayout::TransferDstOptimal),
+            }],
+            CommandInner::ClearDepthStencilImage { image, .. } => vec![ResourceAccess {
+                key: image_key(image),
+                stage: PipelineStageFlags::TRANSFER,
+                access: AccessFlags::TRANSFER_WRITE,
+                layout: Some(ImageLayout::TransferDstOptimal),
+            }],
+            CommandInner::Present { image, .. } => vec![ResourceAccess {
+                key: image_key(image),
+                stage: PipelineStageFlags::BOTTOM_OF_PIPE,
+                access: AccessFlags::empty(),
+                layout: Some(ImageLayout::PresentSrc),
+            }],
+            CommandInner::SetVertexBuffers { vertex_buffers } => vertex_buffers
+                .iter()
+                .map(|buffer| ResourceAccess {
+                    key: buffer_key(buffer),
+                    stage: PipelineStageFlags::VERTEX_INPUT,
+                    access: AccessFlags::VERTEX_ATTRIBUTE_READ,
+                    layout: None,
+                })
+                .collect(),
+            CommandInner::SetIndexBuffer { index_buffer, .. } => vec![ResourceAccess {
+                key: buffer_key(index_buffer),
+                stage: PipelineStageFlags::VERTEX_INPUT,
+                access: AccessFlags::INDEX_READ,
+                layout: None,
+            }],
+            CommandInner::CopyBuffer { src, dst, .. } => vec![
+                ResourceAccess {
+                    key: buffer_key(src),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_READ,
+                    layout: None,
+                },
+                ResourceAccess {
+                    key: buffer_key(dst),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_WRITE,
+                    layout: None,
+                },
+            ],
+            CommandInner::CopyBufferToImage {
+                src_buffer,
+                dst_image,
+                ..
+            } => vec![
+                ResourceAccess {
+                    key: buffer_key(src_buffer),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_READ,
+                    layout: None,
+                },
+                ResourceAccess {
+                    key: image_key(dst_image),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_WRITE,
+                    layout: Some(ImageLayout::TransferDstOptimal),
+                },
+            ],
+            CommandInner::CopyImageToBuffer {
+                src_image,
+                dst_buffer,
+                ..
+            } => vec![
+                ResourceAccess {
+                    key: image_key(src_image),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_READ,
+                    layout: Some(ImageLayout::TransferSrcOptimal),
+                },
+                ResourceAccess {
+                    key: buffer_key(dst_buffer),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_WRITE,
+                    layout: None,
+                },
+            ],
+            CommandInner::CopyImage { src, dst, .. } => vec![
+                ResourceAccess {
+                    key: image_key(src),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_READ,
+                    layout: Some(ImageLayout::TransferSrcOptimal),
+                },
+                ResourceAccess {
+                    key: image_key(dst),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_WRITE,
+                    layout: Some(ImageLayout::TransferDstOptimal),
+                },
+            ],
+            CommandInner::BlitImage { src, dst, .. } => vec![
+                ResourceAccess {
+                    key: image_key(src),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_READ,
+                    layout: Some(ImageLayout::TransferSrcOptimal),
+                },
+                ResourceAccess {
+                    key: image_key(dst),
+                    stage: PipelineStageFlags::TRANSFER,
+                    access: AccessFlags::TRANSFER_WRITE,
+                    layout: Some(ImageLayout::TransferDstOptimal),
+                },
+            ],
+            CommandInner::DrawIndirect { buffer, .. } => vec![ResourceAccess {
+                key: buffer_key(buffer),
+                stage: PipelineStageFlags::DRAW_INDIRECT,
+                access: AccessFlags::INDIRECT_COMMAND_READ,
+                layout: None,
+            }],
+            CommandInner::DrawIndexedIndirect { buffer, .. } => vec![ResourceAccess {
+                key: buffer_key(buffer),
+                stage: PipelineStageFlags::DRAW_INDIRECT,
+                access: AccessFlags::INDIRECT_COMMAND_READ,
+                layout: None,
+            }],
+            // Expanded away by `expand_execute_commands` before this is ever called; its nested
+            // commands report their own resource accesses once spliced into the stream.
+            CommandInner::ExecuteCommands { .. }
+            | CommandInner::PipelineBarrier { .. }
+            | CommandInner::DrawHeader { .. }
+            | CommandInner::SetDescriptorSets { .. }
+            | CommandInner::SetFramebuffer { .. }
+            | CommandInner::SetScissors { .. }
+            | CommandInner::SetViewports { .. }
+            | CommandInner::Draw { .. }
+            | CommandInner::DrawIndexed { .. }
+            // Query pools aren't tracked by the automatic synchronization pass: a pool isn't a
+            // `Buffer`/`Image`, and queries don't alias any resource a barrier would protect.
+            | CommandInner::ResetQueryPool { .. }
+            | CommandInner::WriteTimestamp { .. }
+            | CommandInner::BeginQuery { .. }
+            | CommandInner::EndQuery { .. }
+            // FIXME: `scratch`'s write hazard isn't tracked because `PipelineStageFlags` has no
+            // acceleration-structure-build pipeline stage to anchor it at; build an explicit
+            // `pipeline_barrier` around `build_blas`/`build_tlas` until it does.
+            | CommandInner::BuildAccelerationStructure { .. } => Vec::new(),
+        }
+    }
+}
+
+fn image_key<'a, R: RendererBackend>(image: &Image<'a, R>) -> ResourceKey {
+    ResourceKey::Image(image.image as *const _ as usize)
+}
+
+fn buffer_key<'a, R: RendererBackend>(buffer: &BufferTypeless<'a, R>) -> ResourceKey {
+    ResourceKey::Buffer(buffer.buffer as *const _ as usize)
+}
+
+/// Decides whether `access` conflicts with the last recorded `state` of its resource, and what the
+/// tracked state should become afterwards.
+///
+/// A write after a read or write, or an image needing a layout different from the one it's
+/// currently in, always conflicts and starts a fresh tracking epoch at exactly `access`'s
+/// stage/access/layout. A read after a read with a matching layout doesn't conflict — but its
+/// stage and access masks are folded (OR'd) into the tracked state rather than replacing it, so a
+/// later write's barrier still syncs against every reader since the last write, not just the most
+/// recent one. Without this, a write following two reads at different pipeline stages (e.g.
+/// fragment-shader then vertex-shader) would only wait on the second reader, leaving the first
+/// free to still be in flight when the write clobbers the resource.
+fn next_resource_state(
+    state: Option<ResourceState>,
+    access: &ResourceAccess,
+) -> (bool, ResourceState) {
+    match state {
+        Some(state) => {
+            let needs_barrier = state.access.is_write()
+                || access.access.is_write()
+                || state.layout != access.layout;
+
+            let next = if needs_barrier {
+                ResourceState {
+                    stage: access.stage,
+                    access: access.access,
+                    layout: access.layout,
+                }
+            } else {
+                ResourceState {
+                    stage: state.stage | access.stage,
+                    access: state.access | access.access,
+                    layout: access.layout,
+                }
+            };
+
+            (needs_barrier, next)
+        }
+        None => (
+            false,
+            ResourceState {
+                stage: access.stage,
+                access: access.access,
+                layout: access.layout,
+            },
+        ),
+    }
+}
+
+/// Walks `fused` (already sorted by sortkey) and splices a [CommandInner::PipelineBarrier]
+/// immediately before any command whose resource access conflicts with the last recorded state of
+/// that resource: a write after a read or write, or an image needing a layout different from the
+/// one it's currently in. Reads that follow reads with no intervening write, and no layout
+/// mismatch, need no barrier, but accumulate into the tracked state (see [next_resource_state]) so
+/// a later write still syncs against all of them.
+fn insert_pipeline_barriers<'a, R: RendererBackend>(
+    fused: Vec<Command<'a, R>>,
+) -> Vec<Command<'a, R>> {
+    let mut states: HashMap<ResourceKey, ResourceState> = HashMap::new();
+    let mut out = Vec::with_capacity(fused.len());
+
+    for cmd in fused {
+        let mut barriers = Vec::new();
+        let mut src_stage_mask = PipelineStageFlags::empty();
+        let mut dst_stage_mask = PipelineStageFlags::empty();
+
+        for access in cmd.cmd.resource_accesses() {
+            let prior = states.get(&access.key).copied();
+            let (needs_barrier, next_state) = next_resource_state(prior, &access);
+
+            if needs_barrier {
+                // `prior` is always `Some` here: `next_resource_state` only reports a conflict
+                // once there's a previously recorded state to conflict with.
+                let state = prior.unwrap();
+                src_stage_mask |= state.stage;
+                dst_stage_mask |= access.stage;
+
+                let mut barrier = MemoryBarrier::new(access.key, state.access, access.access);
+                if let (Some(old_layout), Some(new_layout)) = (state.layout, access.layout) {
+                    if old_layout != new_layout {
+                        barrier = barrier.with_layout_transition(old_layout, new_layout);
+                    }
+                }
+                barriers.push(barrier);
+            }
+
+            states.insert(access.key, next_state);
+        }
+
+        if !barriers.is_empty() {
+            out.push(Command {
+                sortkey: cmd.sortkey,
+                cmd: CommandInner::PipelineBarrier {
+                    src_stage_mask,
+                    dst_stage_mask,
+                    memory_barriers: barriers,
+                },
+            });
+        }
+
+        out.push(cmd);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod sync_tests {
+    use super::*;
+
+    fn access(key: ResourceKey, stage: PipelineStageFlags, access: AccessFlags) -> ResourceAccess {
+        ResourceAccess {
+            key,
+            stage,
+            access,
+            layout: None,
+        }
+    }
+
+    fn image_access(
+        key: ResourceKey,
+        stage: PipelineStageFlags,
+        access: AccessFlags,
+        layout: ImageLayout,
+    ) -> ResourceAccess {
+        ResourceAccess {
+            key,
+            stage,
+            access,
+            layout: Some(layout),
         }
     }
 
-    fused.sort_by(|cmd_a, cmd_b| cmd_a.sortkey.cmp(&cmd_b.sortkey));
-    fused
+    #[test]
+    fn first_access_never_needs_a_barrier() {
+        let a = access(
+            ResourceKey::Buffer(1),
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+        );
+        let (needs_barrier, state) = next_resource_state(None, &a);
+        assert!(!needs_barrier);
+        assert_eq!(state.stage, PipelineStageFlags::TRANSFER);
+        assert_eq!(state.access, AccessFlags::TRANSFER_WRITE);
+    }
+
+    #[test]
+    fn write_after_write_needs_a_barrier() {
+        let key = ResourceKey::Buffer(1);
+        let w1 = access(
+            key,
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+        );
+        let (_, state) = next_resource_state(None, &w1);
+
+        let w2 = access(
+            key,
+            PipelineStageFlags::COMPUTE_SHADER,
+            AccessFlags::SHADER_WRITE,
+        );
+        let (needs_barrier, next) = next_resource_state(Some(state), &w2);
+        assert!(needs_barrier);
+        assert_eq!(next.stage, PipelineStageFlags::COMPUTE_SHADER);
+        assert_eq!(next.access, AccessFlags::SHADER_WRITE);
+    }
+
+    #[test]
+    fn read_after_write_needs_a_barrier() {
+        let key = ResourceKey::Buffer(1);
+        let w = access(
+            key,
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+        );
+        let (_, state) = next_resource_state(None, &w);
+
+        let r = access(
+            key,
+            PipelineStageFlags::VERTEX_SHADER,
+            AccessFlags::VERTEX_ATTRIBUTE_READ,
+        );
+        let (needs_barrier, next) = next_resource_state(Some(state), &r);
+        assert!(needs_barrier);
+        assert_eq!(next.stage, PipelineStageFlags::VERTEX_SHADER);
+        assert_eq!(next.access, AccessFlags::VERTEX_ATTRIBUTE_READ);
+    }
+
+    #[test]
+    fn layout_transition_needs_a_barrier() {
+        let key = ResourceKey::Image(1);
+        let write = image_access(
+            key,
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+            ImageLayout::TransferDstOptimal,
+        );
+        let (_, state) = next_resource_state(None, &write);
+
+        let read = image_access(
+            key,
+            PipelineStageFlags::FRAGMENT_SHADER,
+            AccessFlags::SHADER_READ,
+            ImageLayout::ShaderReadOnlyOptimal,
+        );
+        let (needs_barrier, next) = next_resource_state(Some(state), &read);
+        assert!(needs_barrier);
+        assert_eq!(next.layout, Some(ImageLayout::ShaderReadOnlyOptimal));
+    }
+
+    #[test]
+    fn read_after_read_with_same_layout_needs_no_barrier() {
+        let key = ResourceKey::Image(1);
+        let r1 = image_access(
+            key,
+            PipelineStageFlags::FRAGMENT_SHADER,
+            AccessFlags::SHADER_READ,
+            ImageLayout::ShaderReadOnlyOptimal,
+        );
+        let (_, state) = next_resource_state(None, &r1);
+
+        let r2 = image_access(
+            key,
+            PipelineStageFlags::VERTEX_SHADER,
+            AccessFlags::VERTEX_ATTRIBUTE_READ,
+            ImageLayout::ShaderReadOnlyOptimal,
+        );
+        let (needs_barrier, _) = next_resource_state(Some(state), &r2);
+        assert!(!needs_barrier);
+    }
+
+    #[test]
+    fn write_after_multiple_reads_syncs_against_the_union_of_all_readers() {
+        let key = ResourceKey::Buffer(1);
+
+        let r1 = access(
+            key,
+            PipelineStageFlags::FRAGMENT_SHADER,
+            AccessFlags::SHADER_READ,
+        );
+        let (needs_barrier, state) = next_resource_state(None, &r1);
+        assert!(!needs_barrier);
+
+        let r2 = access(
+            key,
+            PipelineStageFlags::VERTEX_SHADER,
+            AccessFlags::VERTEX_ATTRIBUTE_READ,
+        );
+        let (needs_barrier, state) = next_resource_state(Some(state), &r2);
+        assert!(!needs_barrier);
+        // Both readers' stages/accesses must still be visible, since neither has been
+        // synchronized against yet.
+        assert_eq!(
+            state.stage,
+            PipelineStageFlags::FRAGMENT_SHADER | PipelineStageFlags::VERTEX_SHADER
+        );
+        assert_eq!(
+            state.access,
+            AccessFlags::SHADER_READ | AccessFlags::VERTEX_ATTRIBUTE_READ
+        );
+
+        let w = access(
+            key,
+            PipelineStageFlags::TRANSFER,
+            AccessFlags::TRANSFER_WRITE,
+        );
+        let (needs_barrier, _) = next_resource_state(Some(state), &w);
+        assert!(needs_barrier);
+        // The write's barrier (built from this `state` by `insert_pipeline_barriers`) must wait on
+        // both the fragment-shader and the vertex-shader reader, not just the most recent one.
+        assert_eq!(
+            state.stage,
+            PipelineStageFlags::FRAGMENT_SHADER | PipelineStageFlags::VERTEX_SHADER
+        );
+    }
 }
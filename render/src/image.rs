@@ -0,0 +1,135 @@
+//! Region descriptors for the copy/blit commands in [crate::cmd], mirroring the subset of
+//! `VkBufferImageCopy`/`VkImageCopy`/`VkImageBlit` this crate's backends need.
+
+/// A 3D offset into an image, in texels. The `z` component doubles as the first array layer for
+/// 1D/2D array images, matching Vulkan's convention.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub struct Offset3D {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+/// A 3D extent, in texels.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Extent3D {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+/// Which mip level and array layer range of an image a copy/blit region reads or writes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ImageSubresourceLayers {
+    pub mip_level: u32,
+    pub base_array_layer: u32,
+    pub layer_count: u32,
+}
+
+/// Texel filtering mode used by [crate::cmd::CommandInner::BlitImage] when `src`/`dst` extents
+/// differ.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+/// One region of a buffer↔image copy ([crate::cmd::CommandInner::CopyBufferToImage] /
+/// [crate::cmd::CommandInner::CopyImageToBuffer]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BufferImageCopy {
+    pub buffer_offset: u64,
+    /// Row length, in texels, of the buffer's data as laid out in memory; `0` means tightly
+    /// packed (equal to `image_extent.width`).
+    pub buffer_row_length: u32,
+    /// Image height, in texels, of the buffer's data as laid out in memory; `0` means tightly
+    /// packed (equal to `image_extent.height`).
+    pub buffer_image_height: u32,
+    pub image_subresource: ImageSubresourceLayers,
+    pub image_offset: Offset3D,
+    pub image_extent: Extent3D,
+}
+
+/// One region of an image↔image copy ([crate::cmd::CommandInner::CopyImage]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ImageCopy {
+    pub src_subresource: ImageSubresourceLayers,
+    pub src_offset: Offset3D,
+    pub dst_subresource: ImageSubresourceLayers,
+    pub dst_offset: Offset3D,
+    pub extent: Extent3D,
+}
+
+/// One region of an image blit ([crate::cmd::CommandInner::BlitImage]): `src_offsets`/
+/// `dst_offsets` are the two corners of the source/destination box, so a region can scale (the
+/// boxes have different sizes) as well as flip (the corners are swapped along an axis).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ImageBlit {
+    pub src_subresource: ImageSubresourceLayers,
+    pub src_offsets: [Offset3D; 2],
+    pub dst_subresource: ImageSubresourceLayers,
+    pub dst_offsets: [Offset3D; 2],
+}
+
+/// Checks that `region`'s `image_extent` and subresource layer range are non-degenerate: every
+/// component of `image_extent` is non-zero, and `layer_count` is at least `1`.
+///
+/// Returns a list of human-readable errors; empty if the region is valid.
+pub fn validate_buffer_image_copy(region: &BufferImageCopy) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_extent(&region.image_extent, &mut errors);
+    validate_subresource_layers(&region.image_subresource, &mut errors);
+    errors
+}
+
+/// Checks that `region`'s `extent` and both subresource layer ranges are non-degenerate, and that
+/// the source and destination layer counts match (an image copy can't change layer count, unlike
+/// a blit).
+///
+/// Returns a list of human-readable errors; empty if the region is valid.
+pub fn validate_image_copy(region: &ImageCopy) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_extent(&region.extent, &mut errors);
+    validate_subresource_layers(&region.src_subresource, &mut errors);
+    validate_subresource_layers(&region.dst_subresource, &mut errors);
+    if region.src_subresource.layer_count != region.dst_subresource.layer_count {
+        errors.push(format!(
+            "image copy src layer_count ({}) does not match dst layer_count ({})",
+            region.src_subresource.layer_count, region.dst_subresource.layer_count
+        ));
+    }
+    errors
+}
+
+/// Checks that `region`'s subresource layer ranges are non-degenerate and that their layer counts
+/// match. Unlike [validate_image_copy], the src/dst boxes themselves (`src_offsets`/
+/// `dst_offsets`) are intentionally not compared: a blit is allowed to scale and flip.
+///
+/// Returns a list of human-readable errors; empty if the region is valid.
+pub fn validate_image_blit(region: &ImageBlit) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_subresource_layers(&region.src_subresource, &mut errors);
+    validate_subresource_layers(&region.dst_subresource, &mut errors);
+    if region.src_subresource.layer_count != region.dst_subresource.layer_count {
+        errors.push(format!(
+            "image blit src layer_count ({}) does not match dst layer_count ({})",
+            region.src_subresource.layer_count, region.dst_subresource.layer_count
+        ));
+    }
+    errors
+}
+
+fn validate_extent(extent: &Extent3D, errors: &mut Vec<String>) {
+    if extent.width == 0 || extent.height == 0 || extent.depth == 0 {
+        errors.push(format!(
+            "copy region extent must not have a zero component, got {:?}",
+            extent
+        ));
+    }
+}
+
+fn validate_subresource_layers(subresource: &ImageSubresourceLayers, errors: &mut Vec<String>) {
+    if subresource.layer_count == 0 {
+        errors.push("copy region subresource layer_count must be at least 1".to_string());
+    }
+}
@@ -0,0 +1,43 @@
+//! GPU query pools: timestamps and pipeline statistics, recorded through [crate::cmd].
+//!
+//! FIXME: resolved results should be read back through a `get_query_pool_results` method on
+//! `RendererBackend`, but that trait isn't defined in this crate yet, so this module only covers
+//! allocating a pool and recording queries into a command stream; result retrieval is up to
+//! whatever implements `RendererBackend`.
+use crate::RendererBackend;
+use bitflags::bitflags;
+use derivative::Derivative;
+
+bitflags! {
+    /// Which per-draw statistics a [QueryType::PipelineStatistics] query counts, mirroring the
+    /// subset of `VkQueryPipelineStatisticFlags` this crate's backends need.
+    pub struct PipelineStatisticFlags: u32 {
+        const INPUT_ASSEMBLY_VERTICES = 0b0000_0000_0001;
+        const INPUT_ASSEMBLY_PRIMITIVES = 0b0000_0000_0010;
+        const VERTEX_SHADER_INVOCATIONS = 0b0000_0000_0100;
+        const CLIPPING_INVOCATIONS = 0b0000_0000_1000;
+        const CLIPPING_PRIMITIVES = 0b0000_0001_0000;
+        const FRAGMENT_SHADER_INVOCATIONS = 0b0000_0010_0000;
+        const COMPUTE_SHADER_INVOCATIONS = 0b0000_0100_0000;
+    }
+}
+
+/// What a [QueryPool]'s slots count, fixed for the lifetime of the pool.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum QueryType {
+    /// A single GPU timestamp, in implementation-defined ticks, written by
+    /// [crate::cmd::CommandBuffer::write_timestamp].
+    Timestamp,
+    /// Counters gathered over a [crate::cmd::CommandBuffer::begin_query]/
+    /// [crate::cmd::CommandBuffer::end_query] range, selected by `statistics_flags`.
+    PipelineStatistics(PipelineStatisticFlags),
+}
+
+/// A pool of `count` query slots of uniform [QueryType], allocated and owned by the backend.
+#[derive(Derivative)]
+#[derivative(Copy(bound = ""), Clone(bound = ""))]
+pub struct QueryPool<'a, R: RendererBackend> {
+    pub pool: &'a R::QueryPool,
+    pub ty: QueryType,
+    pub count: u32,
+}
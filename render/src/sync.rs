@@ -0,0 +1,133 @@
+//! Resource-access flags and pipeline barriers.
+//!
+//! [MemoryBarrier] and the flag types here are consumed both by
+//! [crate::cmd::CommandBuffer::pipeline_barrier] (explicit, hand-written barriers) and by the
+//! automatic synchronization pass in [crate::cmd::sort_command_buffers], which derives them from
+//! each [crate::cmd::CommandInner]'s declared resource accesses.
+use bitflags::bitflags;
+
+bitflags! {
+    /// Pipeline stages a barrier can wait on (`src`) or block (`dst`), mirroring Vulkan's
+    /// `VkPipelineStageFlags`.
+    pub struct PipelineStageFlags: u32 {
+        const TOP_OF_PIPE = 0b0000_0000_0001;
+        const DRAW_INDIRECT = 0b0000_0000_0010;
+        const VERTEX_INPUT = 0b0000_0000_0100;
+        const VERTEX_SHADER = 0b0000_0000_1000;
+        const FRAGMENT_SHADER = 0b0000_0001_0000;
+        const EARLY_FRAGMENT_TESTS = 0b0000_0010_0000;
+        const LATE_FRAGMENT_TESTS = 0b0000_0100_0000;
+        const COLOR_ATTACHMENT_OUTPUT = 0b0000_1000_0000;
+        const COMPUTE_SHADER = 0b0001_0000_0000;
+        const TRANSFER = 0b0010_0000_0000;
+        const BOTTOM_OF_PIPE = 0b0100_0000_0000;
+        const ALL_COMMANDS = 0b1000_0000_0000;
+    }
+}
+
+bitflags! {
+    /// Memory-access flags a barrier synchronizes, mirroring Vulkan's `VkAccessFlags`.
+    pub struct AccessFlags: u32 {
+        const INDIRECT_COMMAND_READ = 0b0000_0000_0001;
+        const INDEX_READ = 0b0000_0000_0010;
+        const VERTEX_ATTRIBUTE_READ = 0b0000_0000_0100;
+        const UNIFORM_READ = 0b0000_0000_1000;
+        const SHADER_READ = 0b0000_0001_0000;
+        const SHADER_WRITE = 0b0000_0010_0000;
+        const COLOR_ATTACHMENT_READ = 0b0000_0100_0000;
+        const COLOR_ATTACHMENT_WRITE = 0b0000_1000_0000;
+        const DEPTH_STENCIL_ATTACHMENT_READ = 0b0001_0000_0000;
+        const DEPTH_STENCIL_ATTACHMENT_WRITE = 0b0010_0000_0000;
+        const TRANSFER_READ = 0b0100_0000_0000;
+        const TRANSFER_WRITE = 0b1000_0000_0000;
+    }
+}
+
+impl AccessFlags {
+    /// Whether any of the write bits are set.
+    pub fn is_write(self) -> bool {
+        self.intersects(
+            AccessFlags::SHADER_WRITE
+                | AccessFlags::COLOR_ATTACHMENT_WRITE
+                | AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE
+                | AccessFlags::TRANSFER_WRITE,
+        )
+    }
+}
+
+/// The layout an image must be in for a given access; mirrors the subset of `VkImageLayout` this
+/// crate's backends care about.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ImageLayout {
+    Undefined,
+    General,
+    ColorAttachmentOptimal,
+    DepthStencilAttachmentOptimal,
+    ShaderReadOnlyOptimal,
+    TransferSrcOptimal,
+    TransferDstOptimal,
+    PresentSrc,
+}
+
+/// Identifies a tracked resource by the address of its backend object, so two [Command]s that
+/// reference the same buffer or image (e.g. copies of the same `&'a R::Buffer`/`&'a R::Image`
+/// handed out by an arena) resolve to the same key.
+///
+/// [Command]: crate::cmd::Command
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ResourceKey {
+    Buffer(usize),
+    Image(usize),
+}
+
+/// A memory barrier between a resource's prior use and its next one: either spliced in
+/// automatically by [crate::cmd::sort_command_buffers]'s synchronization pass, or passed by hand
+/// to [crate::cmd::CommandBuffer::pipeline_barrier] for a dependency the automatic pass can't see
+/// (e.g. a write happening outside of any tracked command buffer).
+#[derive(Copy, Clone, Debug)]
+pub struct MemoryBarrier<R> {
+    pub resource: ResourceKey,
+    pub src_access_mask: AccessFlags,
+    pub dst_access_mask: AccessFlags,
+    /// `Some` for image resources undergoing a layout transition; `None` for buffers, which have
+    /// no layout, and for images that don't need one.
+    pub old_layout: Option<ImageLayout>,
+    pub new_layout: Option<ImageLayout>,
+    _phantom: std::marker::PhantomData<fn() -> R>,
+}
+
+impl<R> MemoryBarrier<R> {
+    pub fn new(
+        resource: ResourceKey,
+        src_access_mask: AccessFlags,
+        dst_access_mask: AccessFlags,
+    ) -> MemoryBarrier<R> {
+        MemoryBarrier {
+            resource,
+            src_access_mask,
+            dst_access_mask,
+            old_layout: None,
+            new_layout: None,
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    pub fn with_layout_transition(
+        mut self,
+        old_layout: ImageLayout,
+        new_layout: ImageLayout,
+    ) -> MemoryBarrier<R> {
+        self.old_layout = Some(old_layout);
+        self.new_layout = Some(new_layout);
+        self
+    }
+}
+
+/// The last recorded state of a tracked resource, updated as the synchronization pass walks the
+/// sorted command list.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ResourceState {
+    pub(crate) stage: PipelineStageFlags,
+    pub(crate) access: AccessFlags,
+    pub(crate) layout: Option<ImageLayout>,
+}
@@ -1,4 +1,5 @@
 use std::cell::{Cell, Ref, RefCell, RefMut};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{stdout, Write};
 use std::mem;
@@ -177,6 +178,597 @@ type RenderPasses = IdVec<RenderPassId, RenderPass>;
     }
 }*/
 
+//--------------------------------------------------------------------------------------------------
+// Access types
+//
+// A vk-sync-style enum of "how a task uses a resource", each variant carrying a static mapping to
+// the `(stage, access, layout)` triple a barrier needs, so builders declare *what* they're doing
+// with a resource instead of hand-assembling masks.
+//
+// FIXME: `TaskOutputRef::set_read`/`set_write` and `FrameGraph::add_dependency`/
+// `add_image_barrier_access_flags` are defined in this file, but the `Dependency`/`BarrierDetail`
+// types holding the `src_access_mask`/`dst_access_mask` fields are declared in `dependency.rs`, and
+// the task builders calling them in `graphics.rs`/`compute.rs`/`present.rs`, none of which exist in
+// this crate snapshot — so task builders can't be switched over to taking `&[AccessType]` "previous"/
+// "next" lists yet, and `add_dependency` can't be rewritten to OR masks from them in place of the
+// raw `vk::PipelineStageFlags`/`vk::AccessFlags` fields it takes today. [AccessType::stage_access_layout]
+// and [derive_barrier] below are the declarative pieces that rewrite would call into; wire them in
+// at the two call sites above once those files land.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum AccessType {
+    Nothing,
+    IndirectBuffer,
+    IndexBuffer,
+    VertexBuffer,
+    VertexShaderReadUniformBuffer,
+    VertexShaderReadSampledImage,
+    FragmentShaderReadUniformBuffer,
+    FragmentShaderReadSampledImage,
+    ComputeShaderReadUniformBuffer,
+    ComputeShaderReadSampledImage,
+    ComputeShaderWrite,
+    ColorAttachmentRead,
+    ColorAttachmentWrite,
+    DepthStencilAttachmentRead,
+    DepthStencilAttachmentWrite,
+    TransferRead,
+    TransferWrite,
+    HostRead,
+    HostWrite,
+    PresentRead,
+}
+
+impl AccessType {
+    /// Whether this access only reads the resource: consecutive reads of the same resource don't
+    /// need a barrier between them, so [derive_barrier] only orders a "next" access against a
+    /// "previous" one when at least one of them is a write.
+    fn is_read_only(self) -> bool {
+        match self {
+            AccessType::IndirectBuffer
+            | AccessType::IndexBuffer
+            | AccessType::VertexBuffer
+            | AccessType::VertexShaderReadUniformBuffer
+            | AccessType::VertexShaderReadSampledImage
+            | AccessType::FragmentShaderReadUniformBuffer
+            | AccessType::FragmentShaderReadSampledImage
+            | AccessType::ComputeShaderReadUniformBuffer
+            | AccessType::ComputeShaderReadSampledImage
+            | AccessType::ColorAttachmentRead
+            | AccessType::DepthStencilAttachmentRead
+            | AccessType::TransferRead
+            | AccessType::HostRead
+            | AccessType::PresentRead => true,
+            AccessType::Nothing
+            | AccessType::ComputeShaderWrite
+            | AccessType::ColorAttachmentWrite
+            | AccessType::DepthStencilAttachmentWrite
+            | AccessType::TransferWrite
+            | AccessType::HostWrite => false,
+        }
+    }
+
+    /// The `(pipeline stage, access mask, image layout)` triple this access happens at.
+    fn stage_access_layout(self) -> (vk::PipelineStageFlags, vk::AccessFlags, vk::ImageLayout) {
+        match self {
+            AccessType::Nothing => (
+                vk::PIPELINE_STAGE_TOP_OF_PIPE_BIT,
+                vk::AccessFlags::empty(),
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::IndirectBuffer => (
+                vk::PIPELINE_STAGE_DRAW_INDIRECT_BIT,
+                vk::ACCESS_INDIRECT_COMMAND_READ_BIT,
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::IndexBuffer => (
+                vk::PIPELINE_STAGE_VERTEX_INPUT_BIT,
+                vk::ACCESS_INDEX_READ_BIT,
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::VertexBuffer => (
+                vk::PIPELINE_STAGE_VERTEX_INPUT_BIT,
+                vk::ACCESS_VERTEX_ATTRIBUTE_READ_BIT,
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::VertexShaderReadUniformBuffer => (
+                vk::PIPELINE_STAGE_VERTEX_SHADER_BIT,
+                vk::ACCESS_SHADER_READ_BIT,
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::VertexShaderReadSampledImage => (
+                vk::PIPELINE_STAGE_VERTEX_SHADER_BIT,
+                vk::ACCESS_SHADER_READ_BIT,
+                vk::ImageLayout::ShaderReadOnlyOptimal,
+            ),
+            AccessType::FragmentShaderReadUniformBuffer => (
+                vk::PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+                vk::ACCESS_SHADER_READ_BIT,
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::FragmentShaderReadSampledImage => (
+                vk::PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+                vk::ACCESS_SHADER_READ_BIT,
+                vk::ImageLayout::ShaderReadOnlyOptimal,
+            ),
+            AccessType::ComputeShaderReadUniformBuffer => (
+                vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+                vk::ACCESS_SHADER_READ_BIT,
+                vk::ImageLayout::Undefined,
+            ),
+            AccessType::ComputeShaderReadSampledImage => (
+                vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+                vk::ACCESS_SHADER_READ_BIT,
+                vk::ImageLayout::ShaderReadOnlyOptimal,
+            ),
+            AccessType::ComputeShaderWrite => (
+                vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+                vk::ACCESS_SHADER_WRITE_BIT,
+                vk::ImageLayout::General,
+            ),
+            AccessType::ColorAttachmentRead => (
+                vk::PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT,
+                vk::ACCESS_COLOR_ATTACHMENT_READ_BIT,
+                vk::ImageLayout::ColorAttachmentOptimal,
+            ),
+            AccessType::ColorAttachmentWrite => (
+                vk::PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT,
+                vk::ACCESS_COLOR_ATTACHMENT_WRITE_BIT,
+                vk::ImageLayout::ColorAttachmentOptimal,
+            ),
+            AccessType::DepthStencilAttachmentRead => (
+                vk::PIPELINE_STAGE_EARLY_FRAGMENT_TESTS_BIT,
+                vk::ACCESS_DEPTH_STENCIL_ATTACHMENT_READ_BIT,
+                vk::ImageLayout::DepthStencilAttachmentOptimal,
+            ),
+            AccessType::DepthStencilAttachmentWrite => (
+                vk::PIPELINE_STAGE_LATE_FRAGMENT_TESTS_BIT,
+                vk::ACCESS_DEPTH_STENCIL_ATTACHMENT_WRITE_BIT,
+                vk::ImageLayout::DepthStencilAttachmentOptimal,
+            ),
+            AccessType::TransferRead => (
+                vk::PIPELINE_STAGE_TRANSFER_BIT,
+                vk::ACCESS_TRANSFER_READ_BIT,
+                vk::ImageLayout::TransferSrcOptimal,
+            ),
+            AccessType::TransferWrite => (
+                vk::PIPELINE_STAGE_TRANSFER_BIT,
+                vk::ACCESS_TRANSFER_WRITE_BIT,
+                vk::ImageLayout::TransferDstOptimal,
+            ),
+            AccessType::HostRead => (
+                vk::PIPELINE_STAGE_HOST_BIT,
+                vk::ACCESS_HOST_READ_BIT,
+                vk::ImageLayout::General,
+            ),
+            AccessType::HostWrite => (
+                vk::PIPELINE_STAGE_HOST_BIT,
+                vk::ACCESS_HOST_WRITE_BIT,
+                vk::ImageLayout::General,
+            ),
+            AccessType::PresentRead => (
+                vk::PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
+                vk::AccessFlags::empty(),
+                vk::ImageLayout::PresentSrcKhr,
+            ),
+        }
+    }
+}
+
+/// Derives `(src_stage_mask, dst_stage_mask, src_access_mask, dst_access_mask, new_layout)` for a
+/// barrier separating every access in `previous` from every access in `next`, OR-ing each group's
+/// stage/access masks together. `new_layout` is taken from `next`'s first entry: every access a
+/// resource is used under within the same barrier is expected to agree on a layout, falling back to
+/// `General` (the one layout every access type is valid under) when `next` mixes incompatible ones.
+///
+/// If every access in `previous` and `next` is [AccessType::is_read_only], no ordering is actually
+/// required between them (read-after-read needs no barrier): the returned access masks are empty,
+/// and so are the stage masks unless `new_layout` differs from the layout of some access in
+/// `previous`, in which case they are kept for the layout transition.
+pub(crate) fn derive_barrier(
+    previous: &[AccessType],
+    next: &[AccessType],
+) -> (
+    vk::PipelineStageFlags,
+    vk::PipelineStageFlags,
+    vk::AccessFlags,
+    vk::AccessFlags,
+    vk::ImageLayout,
+) {
+    let all_reads =
+        previous.iter().all(|a| a.is_read_only()) && next.iter().all(|a| a.is_read_only());
+
+    let mut src_stage_mask = vk::PipelineStageFlags::empty();
+    let mut src_access_mask = vk::AccessFlags::empty();
+    for &access in previous {
+        let (stage, accessmask, _layout) = access.stage_access_layout();
+        src_stage_mask |= stage;
+        if !all_reads {
+            src_access_mask |= accessmask;
+        }
+    }
+
+    let mut dst_stage_mask = vk::PipelineStageFlags::empty();
+    let mut dst_access_mask = vk::AccessFlags::empty();
+    let mut new_layout = None;
+    for &access in next {
+        let (stage, accessmask, layout) = access.stage_access_layout();
+        dst_stage_mask |= stage;
+        if !all_reads {
+            dst_access_mask |= accessmask;
+        }
+        new_layout = match new_layout {
+            None => Some(layout),
+            Some(existing) if existing == layout => Some(existing),
+            // used under two incompatible layouts in the same barrier: `General` is valid for
+            // every access type, so fall back to it rather than picking one arbitrarily.
+            Some(_) => Some(vk::ImageLayout::General),
+        };
+    }
+    let new_layout = new_layout.unwrap_or(vk::ImageLayout::Undefined);
+
+    // read-after-read with no layout transition: nothing to order, so no barrier at all.
+    if all_reads
+        && previous
+            .iter()
+            .all(|a| a.stage_access_layout().2 == new_layout)
+    {
+        src_stage_mask = vk::PipelineStageFlags::empty();
+        dst_stage_mask = vk::PipelineStageFlags::empty();
+    }
+
+    (
+        src_stage_mask,
+        dst_stage_mask,
+        src_access_mask,
+        dst_access_mask,
+        new_layout,
+    )
+}
+
+//--------------------------------------------------------------------------------------------------
+// Transient memory aliasing
+//
+// A guillotine/free-region packer: aliases transient images/buffers into a small number of shared
+// `VkDeviceMemory` blocks whenever their lifetimes (expressed as indices into the schedule's task
+// ordering) don't overlap, which is what actually realizes the VRAM savings
+// `ScheduleOptimizationProfile::MaximizeAliasing`'s ordering is chosen for.
+//
+// FIXME: wiring this into `Frame::submit` needs two things `alloc.rs`/`resource.rs`/`sched.rs`
+// would supply in a complete build but don't exist in this crate snapshot: (1) each transient
+// resource's `(first_use, last_use)` pair as indices into the `schedule()` ordering — the lower
+// bound being the task that first produced it (tracked by `Resources::create_image`/
+// `create_buffer`, not visible here) and the upper bound `collect_last_uses_of_image`/
+// `collect_last_uses_of_buffer`'s result mapped through that ordering; and (2) each resource's
+// `vk::MemoryRequirements`, which only come back from `vkGetImageMemoryRequirements`/
+// `vkGetBufferMemoryRequirements` once the (currently nonexistent) executor actually creates the
+// underlying `VkImage`/`VkBuffer` handles. `Frame::submit` should call `alias_transient_resources`
+// with that data once those pieces exist; imported/persistent images from `import_image` must never
+// appear in the `lifetimes` passed to it.
+
+/// One free byte range within a [MemoryBlock].
+#[derive(Copy, Clone, Debug)]
+struct FreeSpan {
+    offset: u64,
+    size: u64,
+}
+
+/// A single shared `VkDeviceMemory` allocation the aliasing packer carves up, tracked as a
+/// guillotine free-list: every byte is either free (listed in `free_spans`, kept sorted and
+/// coalesced) or bound to exactly one currently-live resource.
+#[derive(Debug)]
+struct MemoryBlock {
+    /// Intersection of every resource placed here's `memoryRequirements.memory_type_bits`, so a new
+    /// resource can only be placed here if it's compatible with every memory type this block could
+    /// still be backed by.
+    memory_type_bits: u32,
+    size: u64,
+    free_spans: Vec<FreeSpan>,
+}
+
+impl MemoryBlock {
+    fn new(memory_type_bits: u32, size: u64) -> MemoryBlock {
+        MemoryBlock {
+            memory_type_bits,
+            size,
+            free_spans: vec![FreeSpan { offset: 0, size }],
+        }
+    }
+
+    /// Finds the first free span able to fit `size` bytes aligned to `alignment`, splits it, and
+    /// returns the aligned offset chosen within it.
+    fn try_alloc(&mut self, size: u64, alignment: u64) -> Option<u64> {
+        for i in 0..self.free_spans.len() {
+            let span = self.free_spans[i];
+            let aligned_offset = align_up(span.offset, alignment);
+            let padding = aligned_offset - span.offset;
+            if padding > span.size || span.size - padding < size {
+                continue;
+            }
+            self.free_spans.remove(i);
+            // Leading padding (from alignment) and trailing leftover both become free spans again.
+            if padding > 0 {
+                self.free_spans.push(FreeSpan {
+                    offset: span.offset,
+                    size: padding,
+                });
+            }
+            let trailing = span.size - padding - size;
+            if trailing > 0 {
+                self.free_spans.push(FreeSpan {
+                    offset: aligned_offset + size,
+                    size: trailing,
+                });
+            }
+            self.free_spans.sort_by_key(|s| s.offset);
+            return Some(aligned_offset);
+        }
+        None
+    }
+
+    /// Returns a previously allocated `[offset, offset + size)` range to the free list, coalescing
+    /// it with adjacent free spans so fragmentation doesn't accumulate over the frame.
+    fn free(&mut self, offset: u64, size: u64) {
+        self.free_spans.push(FreeSpan { offset, size });
+        self.free_spans.sort_by_key(|s| s.offset);
+        let mut merged: Vec<FreeSpan> = Vec::with_capacity(self.free_spans.len());
+        for span in self.free_spans.drain(..) {
+            match merged.last_mut() {
+                Some(prev) if prev.offset + prev.size == span.offset => {
+                    prev.size += span.size;
+                }
+                _ => merged.push(span),
+            }
+        }
+        self.free_spans = merged;
+    }
+}
+
+fn align_up(offset: u64, alignment: u64) -> u64 {
+    (offset + alignment - 1) / alignment * alignment
+}
+
+/// One transient resource's memory requirements and the `[first_use, last_use]` lifetime interval
+/// it needs a binding for, both expressed as indices into the linear task ordering
+/// `Frame::schedule` produces — the representation [alias_transient_resources] packs.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct TransientResourceLifetime<R> {
+    pub(crate) resource: R,
+    pub(crate) first_use: usize,
+    pub(crate) last_use: usize,
+    pub(crate) requirements: vk::MemoryRequirements,
+}
+
+/// Packs every transient resource in `lifetimes` into as few [MemoryBlock]s as possible: two
+/// resources may share a byte range iff their `[first_use, last_use]` intervals are disjoint.
+/// Resources are processed in `first_use` order; a resource's span is returned to its block's free
+/// list as soon as every other resource still pending has a `first_use` past its `last_use` (a
+/// standard sweep over lifetime-interval endpoints). Resources with disjoint `memory_type_bits` are
+/// never placed in the same block.
+///
+/// Returns each resource's chosen `(block index, offset)` binding, plus each allocated block's
+/// final `(memory_type_bits, size)` so the caller can actually allocate the `VkDeviceMemory` behind
+/// it.
+pub(crate) fn alias_transient_resources<R: Copy + Eq + std::hash::Hash>(
+    lifetimes: &[TransientResourceLifetime<R>],
+) -> (HashMap<R, (usize, u64)>, Vec<(u32, u64)>) {
+    let mut order: Vec<usize> = (0..lifetimes.len()).collect();
+    order.sort_by_key(|&i| lifetimes[i].first_use);
+
+    let mut blocks: Vec<MemoryBlock> = Vec::new();
+    let mut bindings = HashMap::new();
+    // Resources still holding a span, so we know where/when to free it as the sweep advances.
+    let mut live: Vec<(usize, usize, u64, u64)> = Vec::new(); // (last_use, block, offset, size)
+
+    for &i in &order {
+        let lifetime = &lifetimes[i];
+
+        // Free every resource whose last_use has already passed this resource's first_use.
+        live.retain(|&(last_use, block, offset, size)| {
+            if last_use < lifetime.first_use {
+                blocks[block].free(offset, size);
+                false
+            } else {
+                true
+            }
+        });
+
+        let size = lifetime.requirements.size;
+        let alignment = lifetime.requirements.alignment;
+
+        let mut allocated = None;
+        for (block_index, block) in blocks.iter_mut().enumerate() {
+            if block.memory_type_bits & lifetime.requirements.memory_type_bits == 0 {
+                continue;
+            }
+            if let Some(offset) = block.try_alloc(size, alignment) {
+                block.memory_type_bits &= lifetime.requirements.memory_type_bits;
+                allocated = Some((block_index, offset));
+                break;
+            }
+        }
+
+        let (block_index, offset) = allocated.unwrap_or_else(|| {
+            let block_index = blocks.len();
+            let mut block = MemoryBlock::new(lifetime.requirements.memory_type_bits, size);
+            let offset = block
+                .try_alloc(size, alignment)
+                .expect("a freshly created block sized for this resource always fits it");
+            blocks.push(block);
+            (block_index, offset)
+        });
+
+        bindings.insert(lifetime.resource, (block_index, offset));
+        live.push((lifetime.last_use, block_index, offset, size));
+    }
+
+    let block_sizes = blocks
+        .iter()
+        .map(|block| (block.memory_type_bits, block.size))
+        .collect();
+    (bindings, block_sizes)
+}
+
+//--------------------------------------------------------------------------------------------------
+// Lazy subresource initialization
+//
+// Transient images start in `vk::ImageLayout::Undefined` with no defined contents, so a task
+// reading a mip/array-layer range nothing has written yet would sample garbage memory. This tracks,
+// per image, which ranges have been written so far and synthesizes a clear for whatever's read
+// before it's been written, precisely down to the still-uninitialized sub-range rather than
+// clearing the whole image.
+//
+// FIXME: turning a [SynthesizedClear] into an actual inserted task (a `TaskDetails::Transfer` clear,
+// or a render-pass `loadOp = Clear` if `before_task` is graphics and `range` is one of its
+// attachments — see the render pass compaction section below for `attachment_load_store_ops`) and
+// gathering `reads` in the first place both need `Resources`/task builders from `resource.rs`,
+// which doesn't exist in this crate snapshot.
+
+/// A `(mip levels, array layers)` sub-range of an image's subresources, as used for
+/// [InitializationTracker]'s precise (rangemap-style) write/read bookkeeping.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct SubresourceRange {
+    pub(crate) base_mip: u32,
+    pub(crate) mip_count: u32,
+    pub(crate) base_layer: u32,
+    pub(crate) layer_count: u32,
+}
+
+impl SubresourceRange {
+    fn mip_end(self) -> u32 {
+        self.base_mip + self.mip_count
+    }
+
+    fn layer_end(self) -> u32 {
+        self.base_layer + self.layer_count
+    }
+
+    fn intersects(self, other: SubresourceRange) -> bool {
+        self.base_mip < other.mip_end()
+            && other.base_mip < self.mip_end()
+            && self.base_layer < other.layer_end()
+            && other.base_layer < self.layer_end()
+    }
+
+    /// The (possibly empty) rectangular pieces of `self` left over once `cut` is removed from it:
+    /// up to two slices of mip levels entirely outside `cut`'s mip range, plus up to two slices of
+    /// array layers outside `cut`'s layer range within the mip band the two ranges share.
+    fn subtract(self, cut: SubresourceRange) -> Vec<SubresourceRange> {
+        if !self.intersects(cut) {
+            return vec![self];
+        }
+
+        let mut pieces = Vec::new();
+        if self.base_mip < cut.base_mip {
+            pieces.push(SubresourceRange {
+                base_mip: self.base_mip,
+                mip_count: cut.base_mip - self.base_mip,
+                base_layer: self.base_layer,
+                layer_count: self.layer_count,
+            });
+        }
+        if cut.mip_end() < self.mip_end() {
+            pieces.push(SubresourceRange {
+                base_mip: cut.mip_end(),
+                mip_count: self.mip_end() - cut.mip_end(),
+                base_layer: self.base_layer,
+                layer_count: self.layer_count,
+            });
+        }
+
+        let overlap_mip_start = self.base_mip.max(cut.base_mip);
+        let overlap_mip_end = self.mip_end().min(cut.mip_end());
+        if overlap_mip_start < overlap_mip_end {
+            let overlap_mip_count = overlap_mip_end - overlap_mip_start;
+            if self.base_layer < cut.base_layer {
+                pieces.push(SubresourceRange {
+                    base_mip: overlap_mip_start,
+                    mip_count: overlap_mip_count,
+                    base_layer: self.base_layer,
+                    layer_count: cut.base_layer - self.base_layer,
+                });
+            }
+            if cut.layer_end() < self.layer_end() {
+                pieces.push(SubresourceRange {
+                    base_mip: overlap_mip_start,
+                    mip_count: overlap_mip_count,
+                    base_layer: cut.layer_end(),
+                    layer_count: self.layer_end() - cut.layer_end(),
+                });
+            }
+        }
+        pieces
+    }
+}
+
+/// Tracks, per transient image, which subresource ranges have been written by some task so far
+/// during frame-graph construction, so a later read of a range nothing has written yet can be
+/// caught and given a synthesized clear instead of sampling undefined memory.
+#[derive(Default)]
+pub(crate) struct InitializationTracker {
+    written: HashMap<ImageId, Vec<SubresourceRange>>,
+}
+
+impl InitializationTracker {
+    pub(crate) fn new() -> InitializationTracker {
+        InitializationTracker::default()
+    }
+
+    /// Records that `range` of `image` has now been written (by a task declaring a write access).
+    pub(crate) fn mark_written(&mut self, image: ImageId, range: SubresourceRange) {
+        self.written.entry(image).or_insert_with(Vec::new).push(range);
+    }
+
+    /// The portion(s) of `range` not yet covered by any write recorded so far: empty if `range` is
+    /// fully initialized, one or more disjoint sub-ranges otherwise.
+    pub(crate) fn uninitialized_ranges(
+        &self,
+        image: ImageId,
+        range: SubresourceRange,
+    ) -> Vec<SubresourceRange> {
+        let mut pending = vec![range];
+        if let Some(written) = self.written.get(&image) {
+            for &w in written {
+                pending = pending.into_iter().flat_map(|r| r.subtract(w)).collect();
+                if pending.is_empty() {
+                    break;
+                }
+            }
+        }
+        pending
+    }
+}
+
+/// A clear the scheduler must insert immediately before `before_task`, to initialize a sub-range of
+/// `image` that nothing wrote before `before_task` reads it.
+#[derive(Clone, Debug)]
+pub(crate) struct SynthesizedClear {
+    pub(crate) image: ImageId,
+    pub(crate) range: SubresourceRange,
+    pub(crate) before_task: TaskId,
+}
+
+/// Walks `reads` (every task's declared read accesses, in schedule order) against `tracker`,
+/// synthesizing a [SynthesizedClear] for whatever sub-range of each read isn't yet covered by a
+/// prior write, and marks that sub-range written in `tracker` immediately so a later read of the
+/// same range doesn't get a second, redundant clear.
+pub(crate) fn synthesize_clears(
+    tracker: &mut InitializationTracker,
+    reads: &[(TaskId, ImageId, SubresourceRange)],
+) -> Vec<SynthesizedClear> {
+    let mut clears = Vec::new();
+    for &(task, image, range) in reads {
+        for uninitialized in tracker.uninitialized_ranges(image, range) {
+            clears.push(SynthesizedClear {
+                image,
+                range: uninitialized,
+                before_task: task,
+            });
+            tracker.mark_written(image, uninitialized);
+        }
+    }
+    clears
+}
+
 //--------------------------------------------------------------------------------------------------
 impl FrameGraph {
     fn new() -> FrameGraph {
@@ -313,6 +905,447 @@ impl FrameGraph {
 
         uses
     }
+
+    /// Lowers this graph into per-queue linear [RecordStep]s, in `ordering`'s order: consecutive
+    /// incoming edges landing on the same task, from producers on the same queue, are coalesced
+    /// into one [BarrierBatch] (merging `src_stage_mask`/`dst_stage_mask` and collecting every
+    /// buffer/image barrier, rather than one `vkCmdPipelineBarrier` per edge); edges whose producer
+    /// and consumer run on different queues become a [QueueSemaphore] instead, since a pipeline
+    /// barrier can't order work across queues, and are left out of the consumer's batch entirely.
+    fn compile(&self, ordering: &[TaskId]) -> CompiledFrame {
+        let mut compiled = CompiledFrame::default();
+        // Per-queue monotonically increasing timeline value, bumped once per signal allocated on
+        // that queue.
+        let mut next_timeline_value: HashMap<u32, u64> = HashMap::new();
+
+        for &task in ordering {
+            let dst_queue = self.0[task].queue;
+            let mut batch = BarrierBatch::default();
+
+            for edge in self.0.edges_directed(task, Direction::Incoming) {
+                let dep = edge.weight();
+                let src_task = edge.source();
+                let src_queue = self.0[src_task].queue;
+
+                if src_queue != dst_queue {
+                    let value = {
+                        let counter = next_timeline_value.entry(src_queue).or_insert(0);
+                        *counter += 1;
+                        *counter
+                    };
+                    compiled.semaphores.push(QueueSemaphore {
+                        signal_queue: src_queue,
+                        signal_value: value,
+                        wait_queue: dst_queue,
+                        wait_stage_mask: dep.dst_stage_mask,
+                    });
+                    continue;
+                }
+
+                batch.src_stage_mask |= dep.src_stage_mask;
+                batch.dst_stage_mask |= dep.dst_stage_mask;
+                match &dep.barrier {
+                    BarrierDetail::Buffer(barrier) => batch.buffer_barriers.push(BufferBarrierInfo {
+                        id: barrier.id,
+                        src_access_mask: barrier.src_access_mask,
+                        dst_access_mask: barrier.dst_access_mask,
+                    }),
+                    BarrierDetail::Image(barrier) => batch.image_barriers.push(ImageBarrierInfo {
+                        id: barrier.id,
+                        src_access_mask: barrier.src_access_mask,
+                        dst_access_mask: barrier.dst_access_mask,
+                    }),
+                    BarrierDetail::Sequence => {}
+                }
+            }
+
+            compiled
+                .queues
+                .entry(dst_queue)
+                .or_insert_with(Vec::new)
+                .push(RecordStep { barriers: batch, task });
+        }
+
+        compiled
+    }
+}
+
+//--------------------------------------------------------------------------------------------------
+// Compilation
+//
+// Lowers a [FrameGraph] to the linear, per-queue sequence of record steps an executor would
+// actually submit: `FrameGraph::compile`'s `// FIXME subpass barrier` counterpart for ordinary
+// (non-render-pass) barriers, batching same-queue edges landing on the same task into one
+// `vkCmdPipelineBarrier` call and replacing cross-queue edges with timeline-semaphore signal/wait
+// pairs instead, since a pipeline barrier only orders work within a single queue.
+//
+// FIXME: `BufferBarrierInfo`/`ImageBarrierInfo` only carry the fields this module can already see
+// on `dependency::BufferBarrier`/`ImageBarrier` (`id`, `src_access_mask`, `dst_access_mask`) rather
+// than being those types themselves, since `dependency.rs` doesn't exist in this crate snapshot and
+// their full shape (e.g. any subresource range on the image side) isn't visible here. An executor
+// also can't actually record `RecordStep::task`'s body or submit `CompiledFrame`'s queues yet: that
+// needs the task types' (`graphics.rs`/`compute.rs`/`transfer.rs`) own record methods and the
+// (currently nonexistent) code owning the real `VkQueue`/`VkSemaphore` handles.
+
+/// A buffer barrier due before a task runs, as extracted from a frame-graph edge for [BarrierBatch].
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct BufferBarrierInfo {
+    pub(crate) id: BufferId,
+    pub(crate) src_access_mask: vk::AccessFlags,
+    pub(crate) dst_access_mask: vk::AccessFlags,
+}
+
+/// An image barrier due before a task runs, as extracted from a frame-graph edge for
+/// [BarrierBatch].
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ImageBarrierInfo {
+    pub(crate) id: ImageId,
+    pub(crate) src_access_mask: vk::AccessFlags,
+    pub(crate) dst_access_mask: vk::AccessFlags,
+}
+
+/// Every buffer/image barrier due right before a task runs, coalesced from however many same-queue
+/// incoming dependency edges produced them into the arrays a single `vkCmdPipelineBarrier` call
+/// takes.
+#[derive(Debug, Default)]
+pub(crate) struct BarrierBatch {
+    pub(crate) src_stage_mask: vk::PipelineStageFlags,
+    pub(crate) dst_stage_mask: vk::PipelineStageFlags,
+    pub(crate) buffer_barriers: Vec<BufferBarrierInfo>,
+    pub(crate) image_barriers: Vec<ImageBarrierInfo>,
+}
+
+/// One queue's linear execution order after [FrameGraph::compile]: a barrier batch immediately
+/// followed by the task that needs it.
+#[derive(Debug)]
+pub(crate) struct RecordStep {
+    pub(crate) barriers: BarrierBatch,
+    pub(crate) task: TaskId,
+}
+
+/// A timeline semaphore signaled by one queue's producer step and waited on by another's consumer
+/// step, replacing what would otherwise be an impossible cross-queue pipeline barrier.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct QueueSemaphore {
+    /// Which queue signals this semaphore, and at which of its own monotonically increasing
+    /// timeline values.
+    pub(crate) signal_queue: u32,
+    pub(crate) signal_value: u64,
+    /// Which queue waits on it, and at which pipeline stage.
+    pub(crate) wait_queue: u32,
+    pub(crate) wait_stage_mask: vk::PipelineStageFlags,
+}
+
+/// The result of [FrameGraph::compile]: per queue, a linear list of [RecordStep]s ready to record
+/// into a `VkCommandBuffer`, plus the timeline-semaphore signal/wait pairs an executor must submit
+/// alongside them to order work across queues.
+#[derive(Debug, Default)]
+pub(crate) struct CompiledFrame {
+    pub(crate) queues: HashMap<u32, Vec<RecordStep>>,
+    pub(crate) semaphores: Vec<QueueSemaphore>,
+}
+
+//--------------------------------------------------------------------------------------------------
+// Split (event-based) barriers
+//
+// `Dependency::latency` is carried around (and `add_dependency` already takes the max of it when
+// merging edges) but [FrameGraph::compile] ignores it. For a long-latency edge whose producer and consumer have
+// other, independent tasks scheduled between them, folding it into the consumer's [BarrierBatch] as
+// usual stalls the consumer at a full `vkCmdPipelineBarrier` for no reason: a `VkEvent`-based split
+// barrier — `vkCmdSetEvent` right after the producer, `vkCmdWaitEvents` right before the consumer —
+// lets the driver overlap the producer's work with the intervening tasks instead.
+//
+// This is exposed as a `SplitBarrierOptions` flag passed alongside a compile, rather than a new
+// `ScheduleOptimizationProfile` variant as the request also allows, since that enum is declared in
+// `sched.rs`, which doesn't exist in this crate snapshot to add a variant to.
+//
+// FIXME: an executor still needs to actually record `vkCmdSetEvent`/`vkCmdWaitEvents` for every
+// [SplitBarrier] `split_long_latency_barriers` returns (in place of whatever barrier the edge would
+// otherwise have contributed to its consumer's [BarrierBatch] — the caller must itself avoid
+// double-counting by excluding split edges before calling `FrameGraph::compile`) and own the real
+// `VkEvent` pool `EventPool` stands in for; none of that exists yet either.
+
+/// A recycled handle standing in for a `VkEvent`, as allocated by [EventPool].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub(crate) struct EventId(u32);
+
+/// Hands out [EventId]s for one frame's worth of split barriers. Every event allocated this frame is
+/// both set and waited on within the same frame, so a fresh frame just [resets](EventPool::reset)
+/// the counter instead of needing a free list.
+#[derive(Default)]
+pub(crate) struct EventPool {
+    next: u32,
+}
+
+impl EventPool {
+    pub(crate) fn new() -> EventPool {
+        EventPool::default()
+    }
+
+    pub(crate) fn allocate(&mut self) -> EventId {
+        let id = EventId(self.next);
+        self.next += 1;
+        id
+    }
+
+    /// Returns every [EventId] allocated this frame to the pool, ready for the next frame to reuse.
+    pub(crate) fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+/// One dependency edge lowered to a split barrier instead of folding into a [BarrierBatch]:
+/// `vkCmdSetEvent(event, src_stage_mask)` must be recorded right after `producer`, and
+/// `vkCmdWaitEvents(event, dst_stage_mask, ..., buffer_barrier, image_barrier)` right before
+/// `consumer`.
+#[derive(Debug)]
+pub(crate) struct SplitBarrier {
+    pub(crate) event: EventId,
+    pub(crate) producer: TaskId,
+    pub(crate) consumer: TaskId,
+    pub(crate) src_stage_mask: vk::PipelineStageFlags,
+    pub(crate) dst_stage_mask: vk::PipelineStageFlags,
+    pub(crate) buffer_barrier: Option<BufferBarrierInfo>,
+    pub(crate) image_barrier: Option<ImageBarrierInfo>,
+}
+
+/// Tuning for [split_long_latency_barriers]: an edge is only considered for splitting once its
+/// `Dependency::latency` reaches `latency_threshold`.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SplitBarrierOptions {
+    pub(crate) latency_threshold: u32,
+}
+
+/// Finds every edge in `graph` worth lowering to a [SplitBarrier] instead of a single pipeline
+/// barrier: `Dependency::latency` at least `options.latency_threshold`, producer and consumer on the
+/// same queue (a `VkEvent` is queue-local, unlike the [QueueSemaphore]s [FrameGraph::compile] already
+/// uses for cross-queue edges), and separated by at least one other task in `ordering` (adjacent
+/// producer/consumer have no independent work to overlap with, so a full barrier is no worse).
+pub(crate) fn split_long_latency_barriers(
+    graph: &FrameGraph,
+    ordering: &[TaskId],
+    events: &mut EventPool,
+    options: SplitBarrierOptions,
+) -> Vec<SplitBarrier> {
+    let position: HashMap<TaskId, usize> = ordering
+        .iter()
+        .enumerate()
+        .map(|(i, &task)| (task, i))
+        .collect();
+
+    let mut splits = Vec::new();
+    for edge_idx in graph.0.edge_indices() {
+        let dep = &graph.0[edge_idx];
+        if dep.latency < options.latency_threshold {
+            continue;
+        }
+
+        let (producer, consumer) = graph
+            .0
+            .edge_endpoints(edge_idx)
+            .expect("edge_idx came from this graph's own edge_indices");
+        if graph.0[producer].queue != graph.0[consumer].queue {
+            continue;
+        }
+
+        let src_pos = match position.get(&producer) {
+            Some(&p) => p,
+            None => continue,
+        };
+        let dst_pos = match position.get(&consumer) {
+            Some(&p) => p,
+            None => continue,
+        };
+        if dst_pos <= src_pos + 1 {
+            continue;
+        }
+
+        let (buffer_barrier, image_barrier) = match &dep.barrier {
+            BarrierDetail::Buffer(barrier) => (
+                Some(BufferBarrierInfo {
+                    id: barrier.id,
+                    src_access_mask: barrier.src_access_mask,
+                    dst_access_mask: barrier.dst_access_mask,
+                }),
+                None,
+            ),
+            BarrierDetail::Image(barrier) => (
+                None,
+                Some(ImageBarrierInfo {
+                    id: barrier.id,
+                    src_access_mask: barrier.src_access_mask,
+                    dst_access_mask: barrier.dst_access_mask,
+                }),
+            ),
+            // A sequencing constraint carries no actual barrier to split.
+            BarrierDetail::Sequence => continue,
+        };
+
+        splits.push(SplitBarrier {
+            event: events.allocate(),
+            producer,
+            consumer,
+            src_stage_mask: dep.src_stage_mask,
+            dst_stage_mask: dep.dst_stage_mask,
+            buffer_barrier,
+            image_barrier,
+        });
+    }
+    splits
+}
+
+//--------------------------------------------------------------------------------------------------
+// Render pass compaction
+//
+// Groups adjacent `TaskDetails::Graphics` tasks that read/write a compatible set of attachments
+// into a single `VkRenderPass`, each task becoming one subpass, and reclassifies same-render-pass
+// edges as `VkSubpassDependency`s (resolved at `vkCmdNextSubpass`) instead of a standalone
+// `vkCmdPipelineBarrier`. This is what turns the otherwise-inert `RenderPass`/`AttachmentIndex`
+// machinery above into an actual optimization, and is the "merge with existing dependency" this
+// crate's `FrameGraph::add_dependency` leaves as a `// FIXME subpass barrier on an attachment
+// reference` for.
+//
+// FIXME: wiring this into `Frame::submit` needs two things `graphics.rs`/`sched.rs` would supply in
+// a complete build but don't exist in this crate snapshot: (1) each `GraphicsTask`'s actual
+// color/depth-stencil attachments (taken here as an explicit `GraphicsTaskAttachments` the caller
+// must gather, since `GraphicsTask`'s fields aren't visible from this module), and (2)
+// `schedule()`'s real task ordering to compact instead of an arbitrary caller-supplied one. Once
+// both exist, `submit` should call `compact_into_render_passes` on the post-`schedule` ordering and,
+// for every edge `subpass_dependency` returns `Some` for, emit that instead of a standalone barrier.
+
+/// One [TaskDetails::Graphics] task's render-target attachments, as gathered from it ahead of a call
+/// to [compact_into_render_passes].
+#[derive(Clone, Debug)]
+pub(crate) struct GraphicsTaskAttachments {
+    pub(crate) task: TaskId,
+    pub(crate) color: Vec<ImageId>,
+    pub(crate) depth_stencil: Option<ImageId>,
+}
+
+/// A run of graphics tasks compacted into a single render pass, in subpass order: `tasks[i]` is
+/// subpass `i`.
+#[derive(Clone, Debug)]
+pub(crate) struct RenderPassGroup {
+    pub(crate) tasks: Vec<TaskId>,
+}
+
+impl RenderPassGroup {
+    /// The subpass index `task` runs as within this group, or `None` if it isn't part of it.
+    pub(crate) fn subpass_of(&self, task: TaskId) -> Option<u32> {
+        self.tasks.iter().position(|&t| t == task).map(|i| i as u32)
+    }
+}
+
+/// Whether two graphics tasks' attachments are compatible enough to share a render pass: the same
+/// depth/stencil attachment (including both having none), and the same set of color attachments
+/// (order doesn't matter, since each subpass declares its own color references into the pass's
+/// attachment list).
+fn attachments_compatible(a: &GraphicsTaskAttachments, b: &GraphicsTaskAttachments) -> bool {
+    a.depth_stencil == b.depth_stencil
+        && a.color.len() == b.color.len()
+        && a.color.iter().all(|img| b.color.contains(img))
+}
+
+/// Greedily merges adjacent graphics tasks in `ordering` whose attachments are
+/// [compatible](attachments_compatible) into [RenderPassGroup]s. Only tasks present in
+/// `attachments` (i.e. graphics tasks) are considered for merging; a task absent from it (any other
+/// `TaskDetails` variant) ends whatever run was being built, the same as an incompatible attachment
+/// set would.
+pub(crate) fn compact_into_render_passes(
+    ordering: &[TaskId],
+    attachments: &HashMap<TaskId, GraphicsTaskAttachments>,
+) -> Vec<RenderPassGroup> {
+    let mut groups = Vec::new();
+    let mut current: Option<RenderPassGroup> = None;
+
+    for &task in ordering {
+        let task_attachments = match attachments.get(&task) {
+            Some(a) => a,
+            None => {
+                if let Some(group) = current.take() {
+                    groups.push(group);
+                }
+                continue;
+            }
+        };
+
+        let extends_current = current.as_ref().map_or(false, |group| {
+            let last = *group
+                .tasks
+                .last()
+                .expect("a render pass group always has at least one task");
+            attachments_compatible(&attachments[&last], task_attachments)
+        });
+
+        if extends_current {
+            current.as_mut().unwrap().tasks.push(task);
+        } else {
+            if let Some(group) = current.take() {
+                groups.push(group);
+            }
+            current = Some(RenderPassGroup { tasks: vec![task] });
+        }
+    }
+    if let Some(group) = current.take() {
+        groups.push(group);
+    }
+    groups
+}
+
+/// Converts a frame-graph edge between `src_task` and `dst_task`, both within `group`, into a
+/// `VkSubpassDependency` using each task's position in `group` as its subpass index. Returns `None`
+/// if either task isn't actually part of `group`, in which case the edge should remain a standalone
+/// `vkCmdPipelineBarrier` (it crosses a render-pass boundary).
+pub(crate) fn subpass_dependency(
+    group: &RenderPassGroup,
+    src_task: TaskId,
+    dst_task: TaskId,
+    src_stage_mask: vk::PipelineStageFlags,
+    dst_stage_mask: vk::PipelineStageFlags,
+    src_access_mask: vk::AccessFlags,
+    dst_access_mask: vk::AccessFlags,
+) -> Option<vk::SubpassDependency> {
+    let src_subpass = group.subpass_of(src_task)?;
+    let dst_subpass = group.subpass_of(dst_task)?;
+    Some(vk::SubpassDependency {
+        src_subpass,
+        dst_subpass,
+        src_stage_mask,
+        dst_stage_mask,
+        src_access_mask,
+        dst_access_mask,
+        dependency_flags: vk::DependencyFlags::empty(),
+    })
+}
+
+/// Derives an attachment's `loadOp`/`storeOp`/initial layout for the render pass it's used in (its
+/// `final_layout` is always `layout`, the one it's left in by this pass's last use of it):
+/// `Clear`/`Undefined` if this render pass contains the task that first produced the attachment
+/// (`produced_here`), `Load`/`layout` otherwise, since a prior pass left valid contents behind that
+/// must be preserved; `Store` if [FrameGraph::collect_last_uses_of_image] says a task outside this
+/// pass still reads it (`consumed_later`), `DontCare` otherwise, since nothing outside this pass
+/// needs its contents kept around.
+pub(crate) fn attachment_load_store_ops(
+    produced_here: bool,
+    consumed_later: bool,
+    layout: vk::ImageLayout,
+) -> (vk::AttachmentLoadOp, vk::AttachmentStoreOp, vk::ImageLayout) {
+    let load_op = if produced_here {
+        vk::AttachmentLoadOp::Clear
+    } else {
+        vk::AttachmentLoadOp::Load
+    };
+    let store_op = if consumed_later {
+        vk::AttachmentStoreOp::Store
+    } else {
+        vk::AttachmentStoreOp::DontCare
+    };
+    let initial_layout = if produced_here {
+        vk::ImageLayout::Undefined
+    } else {
+        layout
+    };
+    (load_op, store_op, initial_layout)
 }
 
 //--------------------------------------------------------------------------------------------------
@@ -487,6 +1520,15 @@ impl<'ctx> Frame<'ctx> {
         let mut dot = File::create("graph.dot").unwrap();
         self.dump_graphviz(&mut dot, Some(&ordering), false);
     }
+
+    /// Lowers the frame graph to a [CompiledFrame]: per queue, a linear list of barrier-batch-then-
+    /// task record steps, with cross-queue dependencies resolved as timeline-semaphore signal/wait
+    /// pairs instead of pipeline barriers. This is the missing execution backbone between the graph
+    /// `submit` currently only dumps and an executor that could actually record and submit it.
+    pub fn compile(mut self) -> CompiledFrame {
+        let ordering = self.schedule(ScheduleOptimizationProfile::MaximizeAliasing);
+        self.graph.compile(&ordering)
+    }
 }
 
 //--------------------------------------------------------------------------------------------------